@@ -0,0 +1,288 @@
+//! Generation of the operation tables from a Cargo build script.
+//!
+//! ```no_run
+//! // In the main function of build.rs
+//! copar::build::Config::new()
+//!     .input("logs/init.log")
+//!     .rust_out("init.rs")
+//!     .compile();
+//! ```
+//! The generated file is then included in the crate with
+//! `include!(concat!(env!("OUT_DIR"), "/init.rs"));`.
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    CompressionError, FileParsingError, Parser, RustGeneration, Schema, SchemaError,
+    TimestampFormat, UnknownSequenceError,
+};
+
+/// Error preventing the generation of the operation tables
+#[derive(Debug)]
+pub enum BuildError {
+    /// No log was given with [`Config::input`]
+    MissingInput,
+    /// No output was given with [`Config::rust_out`]
+    MissingOutput,
+    /// The output is relative while `OUT_DIR` is not set, outside of a build script
+    MissingOutDir,
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Schema {
+        path: PathBuf,
+        error: SchemaError,
+    },
+    Parsing {
+        path: PathBuf,
+        error: FileParsingError,
+    },
+    Compression {
+        path: PathBuf,
+        error: CompressionError,
+    },
+    UnknownSequence {
+        path: PathBuf,
+        error: UnknownSequenceError,
+    },
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingInput => write!(f, "No input log given to copar::build::Config"),
+            Self::MissingOutput => write!(f, "No output file given to copar::build::Config"),
+            Self::MissingOutDir => write!(
+                f,
+                "OUT_DIR is not set, copar::build::Config must be used from a build script"
+            ),
+            Self::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Schema { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Parsing { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Compression { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::UnknownSequence { path, error } => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Generation of a Rust file from a log, configured from a build script
+#[derive(Debug, Default)]
+pub struct Config {
+    input: Option<PathBuf>,
+    rust_out: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+    sequences: Option<Vec<String>>,
+    schema: Option<PathBuf>,
+    kernel_timestamps: bool,
+    compress: bool,
+    annotate_sources: bool,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config::default()
+    }
+
+    /// Log to parse, relative to the directory of the crate
+    pub fn input(mut self, input_log_file_path: impl Into<PathBuf>) -> Config {
+        self.input = Some(input_log_file_path.into());
+        self
+    }
+
+    /// Rust file to generate, relative to the output directory
+    pub fn rust_out(mut self, output_file_path: impl Into<PathBuf>) -> Config {
+        self.rust_out = Some(output_file_path.into());
+        self
+    }
+
+    /// Directory of the generated file, `OUT_DIR` by default
+    pub fn out_dir(mut self, out_dir: impl Into<PathBuf>) -> Config {
+        self.out_dir = Some(out_dir.into());
+        self
+    }
+
+    /// Generate only these sequences of the log, all of them by default
+    pub fn sequences(mut self, sequence_names: &[impl AsRef<str>]) -> Config {
+        self.sequences = Some(
+            sequence_names
+                .iter()
+                .map(|name| name.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Schema file validating the records of the log, relative to the directory of the crate
+    pub fn schema(mut self, schema_file_path: impl Into<PathBuf>) -> Config {
+        self.schema = Some(schema_file_path.into());
+        self
+    }
+
+    /// Capture the delays between operations from kernel log timestamps
+    pub fn kernel_timestamps(mut self, kernel_timestamps: bool) -> Config {
+        self.kernel_timestamps = kernel_timestamps;
+        self
+    }
+
+    /// Replace repeated operations with repeat operations
+    pub fn compress(mut self, compress: bool) -> Config {
+        self.compress = compress;
+        self
+    }
+
+    /// Comment each entry of the operation tables with the log line of its record
+    pub fn annotate_sources(mut self, annotate_sources: bool) -> Config {
+        self.annotate_sources = annotate_sources;
+        self
+    }
+
+    fn output_path(&self) -> Result<PathBuf, BuildError> {
+        let rust_out = self.rust_out.as_ref().ok_or(BuildError::MissingOutput)?;
+        let out_dir = match self.out_dir.clone() {
+            Some(out_dir) => out_dir,
+            None if rust_out.is_absolute() => PathBuf::new(),
+            None => std::env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or(BuildError::MissingOutDir)?,
+        };
+        Ok(out_dir.join(rust_out))
+    }
+
+    fn generate(&self, input_path: &Path, output_path: &Path) -> Result<(), BuildError> {
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |error| BuildError::Io { path, error }
+        };
+        let input = fs::read_to_string(input_path).map_err(io_error(input_path))?;
+        let mut parser = Parser::new(input.as_bytes());
+        if let Some(file_name) = input_path.file_name() {
+            parser = parser.with_file_name(file_name.to_string_lossy());
+        }
+        if self.kernel_timestamps {
+            parser = parser.with_timestamps(TimestampFormat::KernelLog);
+        }
+        if let Some(schema_path) = self.schema.as_ref() {
+            let schema = fs::read_to_string(schema_path).map_err(io_error(schema_path))?;
+            let schema = Schema::from_toml(&schema).map_err(|error| BuildError::Schema {
+                path: schema_path.clone(),
+                error,
+            })?;
+            parser = parser.with_schema(schema);
+        }
+        let mut model = parser.parse_file().map_err(|error| BuildError::Parsing {
+            path: input_path.to_path_buf(),
+            error,
+        })?;
+        // Same order as `copar build`: the sequences are selected before being compressed
+        if let Some(sequence_names) = self.sequences.as_ref() {
+            model.select_sequences(sequence_names).map_err(|error| {
+                BuildError::UnknownSequence {
+                    path: input_path.to_path_buf(),
+                    error,
+                }
+            })?;
+        }
+        model.set_source_annotations(self.annotate_sources);
+        if self.compress {
+            model.compress().map_err(|error| BuildError::Compression {
+                path: input_path.to_path_buf(),
+                error,
+            })?;
+        }
+
+        let mut content = Vec::new();
+        model.compute_to_rust(&mut content);
+        if let Some(output_dir) = output_path.parent() {
+            fs::create_dir_all(output_dir).map_err(io_error(output_dir))?;
+        }
+        fs::write(output_path, content).map_err(io_error(output_path))
+    }
+
+    /// Generate the Rust file, returning its path. Cargo is told to rerun the build script when
+    /// the log or the schema changes.
+    pub fn try_compile(&self) -> Result<PathBuf, BuildError> {
+        let input_path = self.input.as_ref().ok_or(BuildError::MissingInput)?;
+        println!("cargo:rerun-if-changed={}", input_path.display());
+        if let Some(schema_path) = self.schema.as_ref() {
+            println!("cargo:rerun-if-changed={}", schema_path.display());
+        }
+        let output_path = self.output_path()?;
+        self.generate(input_path, &output_path)?;
+        Ok(output_path)
+    }
+
+    /// Generate the Rust file like [`Config::try_compile`], panicking with the error message to
+    /// fail the build script if the file cannot be generated.
+    pub fn compile(&self) -> PathBuf {
+        match self.try_compile() {
+            Ok(output_path) => output_path,
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Temporary directory of a test, removed even when the test fails
+    struct TestDir(PathBuf);
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn build_config_test() {
+        let test_dir =
+            TestDir(std::env::temp_dir().join(format!("copar_build_test_{}", std::process::id())));
+        let dir = &test_dir.0;
+        fs::create_dir_all(dir).unwrap();
+        let log = "#< init <#\n#= Cmd52 add: x32(0x10) =#\n#> init >#\n";
+        fs::write(dir.join("init.log"), log).unwrap();
+        fs::write(
+            dir.join("bad.log"),
+            "#< init <#\n#= Cmd52 add: x33(0x10) =#\n",
+        )
+        .unwrap();
+
+        let output_path = Config::new()
+            .input(dir.join("init.log"))
+            .rust_out("gen/init.rs")
+            .out_dir(dir)
+            .try_compile()
+            .unwrap();
+        let mut expected_content = Vec::new();
+        Parser::new(log.as_bytes())
+            .with_file_name("init.log")
+            .parse_file()
+            .unwrap()
+            .compute_to_rust(&mut expected_content);
+        assert_eq!(output_path, dir.join("gen/init.rs"));
+        assert_eq!(fs::read(&output_path).unwrap(), expected_content);
+
+        let error = Config::new()
+            .input(dir.join("bad.log"))
+            .rust_out("bad.rs")
+            .out_dir(dir)
+            .try_compile()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BuildError::Parsing {
+                error: FileParsingError::LineError { line_nb: 2, .. },
+                ..
+            }
+        ));
+        assert!(matches!(
+            Config::new().rust_out("init.rs").try_compile(),
+            Err(BuildError::MissingInput)
+        ));
+    }
+}
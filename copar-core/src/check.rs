@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::model::{Model, Sequence};
+use crate::parser::{FileParsingError, Parser};
+use crate::unirecord::{UniRecord, UniRecordArgVariant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckSeverity {
+    /// Suspicious log content that still generates valid code
+    Warning,
+    /// Log content that cannot be parsed or generates wrong code
+    Error,
+}
+
+impl Display for CheckSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CheckIssueKind {
+    /// Record whose arguments differ from the ones of its first occurrence
+    SchemaConflict {
+        record_name: String,
+        first_line_nb: usize,
+        first_schema: String,
+        schema: String,
+    },
+    /// Enum value already used with another enum type
+    InconsistentEnumValue {
+        value: String,
+        enum_type: String,
+        first_enum_type: String,
+        first_line_nb: usize,
+    },
+    /// Record identical to the previous one of the sequence, without delay between them
+    DuplicateRecord { record_name: String },
+    /// Error preventing the parsing of the rest of the log
+    ParsingError(FileParsingError),
+}
+
+impl Display for CheckIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SchemaConflict {
+                record_name,
+                first_line_nb,
+                first_schema,
+                schema,
+            } => write!(
+                f,
+                "Record `{record_name}` has arguments ({schema}) but was first seen at line {first_line_nb} with arguments ({first_schema})"
+            ),
+            Self::InconsistentEnumValue {
+                value,
+                enum_type,
+                first_enum_type,
+                first_line_nb,
+            } => write!(
+                f,
+                "Enum value `{value}` used with type `{enum_type}` but was first used with type `{first_enum_type}` at line {first_line_nb}"
+            ),
+            Self::DuplicateRecord { record_name } => write!(
+                f,
+                "Record `{record_name}` is identical to the previous record of the sequence"
+            ),
+            Self::ParsingError(FileParsingError::LineError { line_error, .. }) => {
+                write!(f, "{line_error}")
+            }
+            Self::ParsingError(FileParsingError::UnknownSequenceCall { sequence_name, .. }) => {
+                write!(f, "Call of unknown sequence `{sequence_name}`")
+            }
+            Self::ParsingError(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Problem found in a log by [`Parser::check`]
+#[derive(Debug)]
+pub struct CheckIssue {
+    pub line_nb: Option<usize>,
+    pub severity: CheckSeverity,
+    pub kind: CheckIssueKind,
+}
+
+/// Collects the issues of the records while they are parsed
+#[derive(Default)]
+pub(crate) struct RecordLinter {
+    record_first_lines: HashMap<String, usize>,
+    enum_value_types: HashMap<String, Vec<(String, usize)>>,
+    pub(crate) issues: Vec<CheckIssue>,
+}
+
+impl RecordLinter {
+    fn format_schema<'a>(members: impl Iterator<Item = (&'a String, String)>) -> String {
+        members
+            .map(|(name, member_type)| format!("{name}: {member_type}"))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// Compare the record to the previous occurrences of its name and of its enum values. Must be
+    /// called before the record is added to the model.
+    pub(crate) fn lint_record(&mut self, model: &Model, record: &UniRecord, line_nb: usize) {
+        let record_name = record.get_name();
+        let record_args = record.get_args();
+
+        let first_line_nb = *self
+            .record_first_lines
+            .entry(record_name.clone())
+            .or_insert(line_nb);
+        if let Some(members) = model.defined_records.get(record_name) {
+            let is_same_schema = members.len() == record_args.len()
+                && members.iter().zip(record_args.iter()).all(|(member, arg)| {
+                    member.member_name == *arg.get_name() && member.member_type == arg.get_type()
+                });
+            if !is_same_schema {
+                self.issues.push(CheckIssue {
+                    line_nb: Some(line_nb),
+                    severity: CheckSeverity::Error,
+                    kind: CheckIssueKind::SchemaConflict {
+                        record_name: record_name.clone(),
+                        first_line_nb,
+                        first_schema: Self::format_schema(
+                            members.iter().map(|member| {
+                                (&member.member_name, member.member_type.to_string())
+                            }),
+                        ),
+                        schema: Self::format_schema(
+                            record_args
+                                .iter()
+                                .map(|arg| (arg.get_name(), arg.get_type().to_string())),
+                        ),
+                    },
+                });
+            }
+        }
+
+        for arg in record_args.iter() {
+            let UniRecordArgVariant::Identifier(arg) = arg else {
+                continue;
+            };
+            let value_types = self.enum_value_types.entry(arg.value.clone()).or_default();
+            if value_types
+                .iter()
+                .any(|(enum_type, _)| *enum_type == arg.enum_type)
+            {
+                continue;
+            }
+            if let Some((first_enum_type, first_line_nb)) = value_types.first() {
+                self.issues.push(CheckIssue {
+                    line_nb: Some(line_nb),
+                    severity: CheckSeverity::Warning,
+                    kind: CheckIssueKind::InconsistentEnumValue {
+                        value: arg.value.clone(),
+                        enum_type: arg.enum_type.clone(),
+                        first_enum_type: first_enum_type.clone(),
+                        first_line_nb: *first_line_nb,
+                    },
+                });
+            }
+            value_types.push((arg.enum_type.clone(), line_nb));
+        }
+    }
+
+    /// Check the last operation added to the sequence. Only the first duplicate of a run of
+    /// identical operations is reported.
+    pub(crate) fn lint_sequence_tail(&mut self, sequence: &Sequence, line_nb: usize) {
+        let table = &sequence.operation_ref_table;
+        let [.., previous, last] = table.as_slice() else {
+            return;
+        };
+        let is_run_start = table.len() < 3 || table[table.len() - 3] != *previous;
+        if last == previous && last.delay_us == 0 && is_run_start {
+            self.issues.push(CheckIssue {
+                line_nb: Some(line_nb),
+                severity: CheckSeverity::Warning,
+                kind: CheckIssueKind::DuplicateRecord {
+                    record_name: last.operation_type.clone(),
+                },
+            });
+        }
+    }
+}
+
+impl Parser {
+    /// Parse the log without generating anything and report the issues of its records, sorted by
+    /// line. The parsing errors are reported as issues, the parsing stopping at the first one.
+    pub fn check(mut self) -> Vec<CheckIssue> {
+        self.linter = Some(RecordLinter::default());
+        let parsing_result = self.parse_sequences();
+        let mut issues = self
+            .linter
+            .take()
+            .map(|linter| linter.issues)
+            .unwrap_or_default();
+        if let Err(e) = parsing_result {
+            let line_nb = match &e {
+                FileParsingError::LineError { line_nb, .. }
+                | FileParsingError::UnknownSequenceCall { line_nb, .. } => Some(*line_nb),
+                FileParsingError::NoSequenceEnd {
+                    last_record_start_line,
+                } => Some(*last_record_start_line),
+                _ => None,
+            };
+            issues.push(CheckIssue {
+                line_nb,
+                severity: CheckSeverity::Error,
+                kind: CheckIssueKind::ParsingError(e),
+            });
+        }
+        issues.sort_by_key(|issue| issue.line_nb);
+        issues
+    }
+}
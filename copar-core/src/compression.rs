@@ -0,0 +1,226 @@
+use crate::model::{Model, OperationTableMember};
+use crate::unirecord::{UniRecord, UniRecordArg, UniRecordArgVariant};
+
+/// Name of the operation introduced by the compression to repeat a part of the table
+pub const REPEAT_RECORD_NAME: &str = "Repeat";
+
+/// Longest sequence of operations searched for repetitions
+const MAX_REPEATED_BODY_LEN: usize = 64;
+
+#[derive(Debug)]
+pub enum CompressionError {
+    /// A record of the log already uses the name of the repeat operation
+    ReservedRecordName(String),
+}
+
+impl std::fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReservedRecordName(name) => write!(
+                f,
+                "Record name `{name}` is reserved for the repeat operations of the compression"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CompressionError {}
+
+impl Model {
+    /// Replace the runs of identical operations and the repeated sub-sequences of the operation
+    /// table with `Repeat` operations.
+    /// A `Repeat { count, body_len }` operation means that the `body_len` operations following it
+    /// have to be executed `count` times. Repeats can be nested, in which case the nested repeat
+    /// and its body all count in the `body_len` of the enclosing repeat.
+    pub fn compress(&mut self) -> Result<(), CompressionError> {
+        if self.defined_records.contains_key(REPEAT_RECORD_NAME)
+            && !self.generated_records.contains(REPEAT_RECORD_NAME)
+        {
+            return Err(CompressionError::ReservedRecordName(
+                REPEAT_RECORD_NAME.to_string(),
+            ));
+        }
+        self.generated_records
+            .insert(REPEAT_RECORD_NAME.to_string());
+
+        for sequence_index in 0..self.sequences.len() {
+            self.compress_sequence(sequence_index);
+        }
+        Ok(())
+    }
+
+    /// Compress the table of the sequence between its phase boundaries, so that no repeat
+    /// crosses the start or end of a phase.
+    fn compress_sequence(&mut self, sequence_index: usize) {
+        let sequence = &mut self.sequences[sequence_index];
+        let operation_ref_table = std::mem::take(&mut sequence.operation_ref_table);
+        let mut boundaries = sequence.phase_boundaries();
+        boundaries.push(operation_ref_table.len());
+
+        let mut compressed_table = Vec::new();
+        let mut index_map = vec![0; operation_ref_table.len() + 1];
+        let mut segment_start = 0;
+        for boundary in boundaries {
+            if boundary < segment_start {
+                continue;
+            }
+            index_map[segment_start] = compressed_table.len();
+            let segment = &operation_ref_table[segment_start..boundary];
+            compressed_table.extend(self.compress_table(segment));
+            index_map[boundary] = compressed_table.len();
+            segment_start = boundary;
+        }
+
+        let sequence = &mut self.sequences[sequence_index];
+        for phase in sequence.phases.iter_mut() {
+            phase.remap(&index_map);
+        }
+        sequence.operation_ref_table = compressed_table;
+    }
+
+    /// Find the body length and number of repetitions saving the most table entries for the
+    /// repetitions starting at the beginning of the table.
+    fn find_best_repetition(table: &[OperationTableMember]) -> Option<(usize, usize)> {
+        let mut best_repetition = None;
+        let mut best_saved_entries = 0;
+        let max_body_len = MAX_REPEATED_BODY_LEN.min(table.len() / 2);
+
+        for body_len in 1..=max_body_len {
+            let body = &table[..body_len];
+            let count = table
+                .chunks_exact(body_len)
+                .take_while(|chunk| *chunk == body)
+                .count();
+            // The repeat operation takes one more entry in the table
+            let saved_entries = ((count - 1) * body_len).saturating_sub(1);
+            if saved_entries > best_saved_entries {
+                best_saved_entries = saved_entries;
+                best_repetition = Some((body_len, count));
+            }
+        }
+
+        best_repetition
+    }
+
+    fn compress_table(&mut self, table: &[OperationTableMember]) -> Vec<OperationTableMember> {
+        let mut compressed_table = Vec::new();
+        let mut index = 0;
+
+        while index < table.len() {
+            if let Some((body_len, count)) = Self::find_best_repetition(&table[index..]) {
+                let body = self.compress_table(&table[index..index + body_len]);
+                let repeat = self.add_repeat_operation(count, body.len());
+                compressed_table.push(repeat);
+                compressed_table.extend(body);
+                index += body_len * count;
+            } else {
+                compressed_table.push(table[index].clone());
+                index += 1;
+            }
+        }
+
+        compressed_table
+    }
+
+    fn add_repeat_operation(&mut self, count: usize, body_len: usize) -> OperationTableMember {
+        let repeat_record = UniRecord::new(
+            REPEAT_RECORD_NAME.to_string(),
+            vec![
+                UniRecordArgVariant::U32(UniRecordArg {
+                    name: String::from("count"),
+                    value: u32::try_from(count).unwrap(),
+                }),
+                UniRecordArgVariant::U32(UniRecordArg {
+                    name: String::from("body_len"),
+                    value: u32::try_from(body_len).unwrap(),
+                }),
+            ],
+        );
+        self.add_operation(repeat_record, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cmd(model: &mut Model, value: u8) -> OperationTableMember {
+        let record = UniRecord::new(
+            String::from("Cmd"),
+            vec![UniRecordArgVariant::X8(UniRecordArg {
+                name: String::from("data"),
+                value,
+            })],
+        );
+        model.add_operation(record, 0)
+    }
+
+    #[test]
+    fn compression_test() {
+        let mut model = Model::default();
+        let a = cmd(&mut model, 0xa);
+        let b = cmd(&mut model, 0xb);
+        let c = cmd(&mut model, 0xc);
+        // a b c c c c a b c c c c a b
+        let table = [&a, &b, &c, &c, &c, &c, &a, &b, &c, &c, &c, &c, &a, &b];
+        model.begin_sequence(String::from("test_sequence"), Vec::new());
+        model.sequences[0].operation_ref_table = table.into_iter().cloned().collect();
+        model.compress().unwrap();
+
+        let compressed_table = &model.sequences[0].operation_ref_table;
+        let compressed: Vec<&str> = compressed_table
+            .iter()
+            .map(|member| member.operation_variant_ref_name.as_str())
+            .collect();
+        // repeat(2, 4) a b repeat(4, 1) c, a b
+        assert_eq!(
+            compressed,
+            [
+                "operation_4",
+                "operation_0",
+                "operation_1",
+                "operation_3",
+                "operation_2",
+                "operation_0",
+                "operation_1"
+            ]
+        );
+        assert_eq!(compressed_table[0].operation_type, "Repeat");
+        assert_eq!(compressed_table[3].operation_type, "Repeat");
+    }
+
+    #[test]
+    fn reserved_repeat_name_test() {
+        let mut model = Model::default();
+        model.begin_sequence(String::from("test_sequence"), Vec::new());
+        let a = cmd(&mut model, 0xa);
+        model.sequences[0].operation_ref_table = vec![a.clone(), a.clone(), a];
+        model.compress().unwrap();
+        // Compressing again reuses the generated repeat operation
+        model.compress().unwrap();
+
+        let mut model = Model::default();
+        model.begin_sequence(String::from("test_sequence"), Vec::new());
+        let logged_repeat = model.add_operation(
+            UniRecord::new(
+                String::from(REPEAT_RECORD_NAME),
+                vec![
+                    UniRecordArgVariant::U32(UniRecordArg {
+                        name: String::from("count"),
+                        value: 2,
+                    }),
+                    UniRecordArgVariant::U32(UniRecordArg {
+                        name: String::from("body_len"),
+                        value: 1,
+                    }),
+                ],
+            ),
+            0,
+        );
+        model.sequences[0].operation_ref_table = vec![logged_repeat];
+        assert!(matches!(
+            model.compress(),
+            Err(CompressionError::ReservedRecordName(_))
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::parser::{LineParsingError, Parser};
+
+/// Record being joined from several lines of the log
+struct MultilineRecord {
+    line_nb: usize,
+    /// Timestamp prefixing the first line of the record, kept to preserve the captured delays
+    timestamp: String,
+    delimiter_token_char: char,
+    content: String,
+}
+
+impl MultilineRecord {
+    /// Append a line of the record, the same way the parser joins them
+    fn push(&mut self, line: &str) {
+        if self.content.ends_with(')') {
+            self.content += " ";
+        }
+        self.content += line;
+    }
+
+    fn to_line(&self) -> String {
+        let delimiter = self.delimiter_token_char;
+        format!(
+            "{}#{delimiter} {} {delimiter}#",
+            self.timestamp, self.content
+        )
+    }
+}
+
+/// Minimal log written by [`Parser::extract`]
+struct ExtractedLog {
+    with_line_numbers: bool,
+    content: String,
+}
+
+impl ExtractedLog {
+    fn push(&mut self, line_nb: usize, line: &str) {
+        if self.with_line_numbers {
+            self.content += &format!("// line {line_nb}\n");
+        }
+        self.content += line;
+        self.content += "\n";
+    }
+}
+
+impl Parser {
+    /// Keep only the records of the sequences of the log, dropping every other line. The
+    /// records written on several lines are joined on a single line and the timestamps of the
+    /// records are kept. With `with_line_numbers`, each record is preceded by a comment giving
+    /// its line in the original log.
+    /// The records are not parsed: parsing the extracted log reports the same errors as the
+    /// original log, at other lines.
+    pub fn extract(mut self, with_line_numbers: bool) -> String {
+        let input_file_buffer = self.input_file_buffer.take().unwrap();
+        let mut extracted_log = ExtractedLog {
+            with_line_numbers,
+            content: String::new(),
+        };
+        let mut sequence_name: Option<&str> = None;
+        let mut multiline_record: Option<MultilineRecord> = None;
+
+        for (line_index, line) in input_file_buffer.lines().enumerate() {
+            let line_nb = line_index + 1;
+            let (_, mut line_content) = Self::strip_timestamp(self.timestamp_format, line);
+            let timestamp = &line[..line.len() - line_content.len()];
+            if line_content.is_empty() {
+                continue;
+            }
+            let res = Self::get_delimited_content(&mut line_content);
+
+            if let Some(record) = multiline_record.as_mut() {
+                match res {
+                    Ok(delimiter_token_char)
+                    | Err(LineParsingError::MissingBeginDelimiter(delimiter_token_char))
+                        if delimiter_token_char == record.delimiter_token_char =>
+                    {
+                        record.push(line_content);
+                        extracted_log.push(record.line_nb, &record.to_line());
+                        multiline_record = None;
+                    }
+                    Err(LineParsingError::UnparsableLine) => record.push(line_content),
+                    _ => (),
+                }
+                continue;
+            }
+
+            match (sequence_name, res) {
+                (None, Ok('<')) => {
+                    // The metadata following the name is kept with the begin record
+                    sequence_name = line_content.split_whitespace().next();
+                    extracted_log.push(line_nb, line);
+                }
+                (None, _) => (),
+                (Some(name), Ok('>')) if line_content == name => {
+                    sequence_name = None;
+                    extracted_log.push(line_nb, line);
+                }
+                (Some(_), Ok(_)) => extracted_log.push(line_nb, line),
+                (Some(_), Err(LineParsingError::MissingEndDelimiter(delimiter_token_char)))
+                    if delimiter_token_char == '=' || delimiter_token_char == '?' =>
+                {
+                    multiline_record = Some(MultilineRecord {
+                        line_nb,
+                        timestamp: timestamp.to_string(),
+                        delimiter_token_char,
+                        content: line_content.to_string(),
+                    });
+                }
+                (Some(_), _) => (),
+            }
+        }
+
+        // A record left open is kept as is for the parsing of the extracted log to fail
+        if let Some(record) = multiline_record {
+            let line = format!(
+                "{}#{} {}",
+                record.timestamp, record.delimiter_token_char, record.content
+            );
+            extracted_log.push(record.line_nb, &line);
+        }
+        extracted_log.content
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Parser, TimestampFormat};
+
+    #[test]
+    fn extract_test() {
+        let log = "\
+driver probe
+#< init <#
+irq 42 handled
+#~ delay ms: u32(1) ~#
+[    1.000000] #= Cmd52 add: x32(0x10)
+[    1.000100] data: x8([0x1,
+0x2]) =#
+unrelated #= text
+#> init >#
+#= Cmd52 add: x32(0x20) data: x8(0x1) =#
+";
+        let extracted_log = Parser::new(log.as_bytes())
+            .with_timestamps(TimestampFormat::KernelLog)
+            .extract(true);
+        assert_eq!(
+            extracted_log,
+            "\
+// line 2
+#< init <#
+// line 4
+#~ delay ms: u32(1) ~#
+// line 5
+[    1.000000] #= Cmd52 add: x32(0x10) data: x8([0x1,0x2]) =#
+// line 9
+#> init >#
+"
+        );
+        let model = Parser::new(extracted_log.as_bytes())
+            .with_timestamps(TimestampFormat::KernelLog)
+            .parse_file()
+            .unwrap();
+        assert_eq!(model.stats(4).operation_count, 1);
+    }
+}
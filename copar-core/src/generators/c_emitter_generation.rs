@@ -0,0 +1,474 @@
+use super::{generate_blank_line, generate_provenance_comment};
+use crate::{
+    model::StructureDefinitionMember,
+    unirecord::{MemberType, EXPECT_RECORD_SUFFIX},
+    Model,
+};
+use stringcase::snake_case;
+
+/// Name of the macro printing the records, defined by the user before including the header
+const PRINTF_MACRO_NAME: &str = "COPAR_LOG_PRINTF";
+/// Name of the loop index printing the arrays, prefixed to avoid conflicts with the arguments
+const ARRAY_INDEX_NAME: &str = "copar_index";
+
+mod private {
+    pub trait Sealed {
+        fn generate_c_emitter_header_pre(&self, output_file: &mut impl std::io::Write);
+        fn generate_c_emitter_header_post(&self, output_file: &mut impl std::io::Write);
+    }
+}
+
+use private::Sealed;
+
+/// Trait allowing copar model to generate a C header printing the records of the model, to
+/// instrument C drivers
+pub trait CEmitterGeneration: private::Sealed {
+    /// Generate the C header from the model
+    fn compute_to_c_emitter(&self, output_h_file: &mut impl std::io::Write);
+}
+
+impl CEmitterGeneration for Model {
+    fn compute_to_c_emitter(&self, output_h_file: &mut impl std::io::Write) {
+        self.generate_c_emitter_header_pre(output_h_file);
+        generate_blank_line(output_h_file);
+        // The nested structures are defined before the structures holding them
+        for (struct_type, struct_members) in self.defined_structs.iter() {
+            generate_c_emitter_struct(self, struct_type, struct_members, output_h_file);
+            generate_blank_line(output_h_file);
+        }
+        for (record_name, record_members) in self.defined_records.iter() {
+            // Calls and repeats are generated by the parser and the compression, not logged
+            if self.generated_records.contains(record_name) {
+                continue;
+            }
+            generate_c_emitter_function(self, record_name, record_members, output_h_file);
+            generate_blank_line(output_h_file);
+        }
+        self.generate_c_emitter_header_post(output_h_file);
+    }
+}
+
+/// Scalar type of the elements of an array type
+fn array_element_type(member_type: &MemberType) -> Option<MemberType> {
+    let element_type = match member_type {
+        MemberType::ArrayOfX8 => MemberType::X8,
+        MemberType::ArrayOfX16 => MemberType::X16,
+        MemberType::ArrayOfX32 => MemberType::X32,
+        MemberType::ArrayOfX64 => MemberType::X64,
+        MemberType::ArrayOfU8 => MemberType::U8,
+        MemberType::ArrayOfU16 => MemberType::U16,
+        MemberType::ArrayOfU32 => MemberType::U32,
+        MemberType::ArrayOfU64 => MemberType::U64,
+        MemberType::ArrayOfI8 => MemberType::I8,
+        MemberType::ArrayOfI16 => MemberType::I16,
+        MemberType::ArrayOfI32 => MemberType::I32,
+        MemberType::ArrayOfI64 => MemberType::I64,
+        MemberType::ArrayOfX128 => MemberType::X128,
+        MemberType::ArrayOfU128 => MemberType::U128,
+        MemberType::ArrayOfI128 => MemberType::I128,
+        MemberType::ArrayOfF32 => MemberType::F32,
+        MemberType::ArrayOfF64 => MemberType::F64,
+        _ => return None,
+    };
+    Some(element_type)
+}
+
+/// Type of the words and function printing a 128-bit integer type, the kernels not always
+/// supporting `__int128`. The scalars are passed as two 64-bit words, the arrays as arrays of
+/// word structures.
+fn int128_printer(member_type: &MemberType) -> Option<(&'static str, &'static str)> {
+    match member_type {
+        MemberType::X128 => Some(("uint64_t", "copar_print_x128")),
+        MemberType::U128 => Some(("uint64_t", "copar_print_u128")),
+        MemberType::I128 => Some(("int64_t", "copar_print_i128")),
+        _ => None,
+    }
+}
+
+/// Words structures and functions printing the 128-bit integers
+const INT128_PRINTERS: &str = "struct copar_u128_words{
+   uint64_t high;
+   uint64_t low;
+};
+
+struct copar_i128_words{
+   int64_t high;
+   uint64_t low;
+};
+
+static inline void copar_print_x128(uint64_t high, uint64_t low)
+{
+   if (high != 0) PRINTF(\"0x%llx%016llx\", (unsigned long long)high, (unsigned long long)low);
+   else PRINTF(\"0x%llx\", (unsigned long long)low);
+}
+
+static inline void copar_print_u128(uint64_t high, uint64_t low)
+{
+   /* Long division by 10 of 32-bit digits, the remainder always fitting in 64 bits */
+   char digits[40];
+   size_t position = sizeof(digits) - 1;
+   digits[position] = '\\0';
+   do {
+      uint32_t words[4] = {(uint32_t)(high >> 32), (uint32_t)high, (uint32_t)(low >> 32), (uint32_t)low};
+      uint64_t remainder = 0;
+      for (size_t index = 0; index < 4; index++) {
+         uint64_t current = (remainder << 32) | words[index];
+         words[index] = (uint32_t)(current / 10);
+         remainder = current % 10;
+      }
+      high = ((uint64_t)words[0] << 32) | words[1];
+      low = ((uint64_t)words[2] << 32) | words[3];
+      digits[--position] = (char)('0' + remainder);
+   } while (high != 0 || low != 0);
+   PRINTF(\"%s\", &digits[position]);
+}
+
+static inline void copar_print_i128(int64_t high, uint64_t low)
+{
+   uint64_t magnitude_high = (uint64_t)high;
+   if (high < 0) {
+      PRINTF(\"-\");
+      low = ~low + 1;
+      magnitude_high = ~magnitude_high + (low == 0);
+   }
+   copar_print_u128(magnitude_high, low);
+}
+";
+
+/// C type, printf format and printf argument of a scalar value. The integers are cast to the
+/// smallest standard type holding them on every platform, `<inttypes.h>` not being available in
+/// every kernel.
+fn printf_value(member_type: &MemberType, value: &str) -> (String, String, String) {
+    let (c_type, format, cast) = match member_type {
+        MemberType::X8 => ("uint8_t", "0x%x", "(unsigned int)"),
+        MemberType::X16 => ("uint16_t", "0x%x", "(unsigned int)"),
+        MemberType::X32 => ("uint32_t", "0x%lx", "(unsigned long)"),
+        MemberType::X64 => ("uint64_t", "0x%llx", "(unsigned long long)"),
+        MemberType::U8 => ("uint8_t", "%u", "(unsigned int)"),
+        MemberType::U16 => ("uint16_t", "%u", "(unsigned int)"),
+        MemberType::U32 => ("uint32_t", "%lu", "(unsigned long)"),
+        MemberType::U64 => ("uint64_t", "%llu", "(unsigned long long)"),
+        MemberType::I8 => ("int8_t", "%d", "(int)"),
+        MemberType::I16 => ("int16_t", "%d", "(int)"),
+        MemberType::I32 => ("int32_t", "%ld", "(long)"),
+        MemberType::I64 => ("int64_t", "%lld", "(long long)"),
+        MemberType::F32 => ("float", "%.9g", "(double)"),
+        MemberType::F64 => ("double", "%.17g", ""),
+        MemberType::Bool => {
+            return (
+                String::from("bool"),
+                String::from("%s"),
+                format!("{value} ? \"true\" : \"false\""),
+            )
+        }
+        // The enum values are printed by name, new values being accepted by the parser
+        MemberType::Identifier(enum_type) => {
+            return (
+                String::from("const char*"),
+                format!("{enum_type}::%s"),
+                value.to_string(),
+            )
+        }
+        _ => unreachable!("Arrays and flags are printed element by element"),
+    };
+    (
+        c_type.to_string(),
+        format.to_string(),
+        format!("{cast}{value}"),
+    )
+}
+
+/// Successive printf calls printing a record
+#[derive(Default)]
+struct PrintfCalls {
+    lines: Vec<String>,
+    format: String,
+    args: Vec<String>,
+}
+
+impl PrintfCalls {
+    fn push(&mut self, format: &str, arg: Option<String>) {
+        self.format += format;
+        self.args.extend(arg);
+    }
+
+    fn flush(&mut self) {
+        if self.format.is_empty() {
+            return;
+        }
+        let args: String = self.args.iter().map(|arg| format!(", {arg}")).collect();
+        self.lines.push(format!(
+            "   {PRINTF_MACRO_NAME}(\"{}\"{args});",
+            std::mem::take(&mut self.format)
+        ));
+        self.args.clear();
+    }
+
+    fn push_line(&mut self, line: String) {
+        self.flush();
+        self.lines.push(line);
+    }
+}
+
+impl private::Sealed for Model {
+    fn generate_c_emitter_header_pre(&self, output_file: &mut impl std::io::Write) {
+        generate_provenance_comment(self, output_file);
+        let sequence_name = self.main_sequence_name().unwrap().to_uppercase();
+        write!(output_file, "#ifndef _{sequence_name}_EMIT_H\n#define _{sequence_name}_EMIT_H\n#include <stdint.h>\n#include <stdbool.h>\n#include <stddef.h>\n").unwrap();
+        generate_blank_line(output_file);
+        writeln!(
+            output_file,
+            "/* Define {PRINTF_MACRO_NAME} before including this file to print the records with another printf-like function */"
+        )
+        .unwrap();
+        write!(
+            output_file,
+            "#ifndef {PRINTF_MACRO_NAME}\n#include <stdio.h>\n#define {PRINTF_MACRO_NAME} printf\n#endif\n"
+        )
+        .unwrap();
+        let members = self
+            .defined_structs
+            .values()
+            .chain(self.defined_records.values());
+        let uses_int128 = members.flatten().any(|member| {
+            int128_printer(&member.member_type).is_some()
+                || array_element_type(&member.member_type)
+                    .is_some_and(|element_type| int128_printer(&element_type).is_some())
+        });
+        if uses_int128 {
+            generate_blank_line(output_file);
+            write!(
+                output_file,
+                "{}",
+                INT128_PRINTERS.replace("PRINTF", PRINTF_MACRO_NAME)
+            )
+            .unwrap();
+        }
+    }
+
+    fn generate_c_emitter_header_post(&self, output_file: &mut impl std::io::Write) {
+        write!(output_file, "#endif").unwrap();
+    }
+}
+
+/// Add the parameters and printf calls printing a member, its value being read from the
+/// parameter of the same name prefixed by `access`
+fn push_c_emitter_member(
+    model: &Model,
+    member: &StructureDefinitionMember,
+    access: &str,
+    parameters: &mut Vec<String>,
+    printf_calls: &mut PrintfCalls,
+) {
+    let member_name = member.member_name.as_str();
+    let value = format!("{access}{member_name}");
+    match &member.member_type {
+        MemberType::Struct(struct_type) => {
+            parameters.push(format!(
+                "const struct copar_{} *{member_name}",
+                snake_case(struct_type)
+            ));
+            printf_calls.push_line(format!(
+                "   copar_print_struct_{}({value});",
+                snake_case(struct_type)
+            ));
+            return;
+        }
+        MemberType::ArrayOfStruct(struct_type) => {
+            // The elements are only separated by commas, as the other arrays
+            parameters.push(format!(
+                "const struct copar_{} *{member_name}",
+                snake_case(struct_type)
+            ));
+            parameters.push(format!("size_t {member_name}_len"));
+            printf_calls.push("[", None);
+            printf_calls.push_line(format!(
+                "   for (size_t {ARRAY_INDEX_NAME} = 0; {ARRAY_INDEX_NAME} < {value}_len; {ARRAY_INDEX_NAME}++) {{"
+            ));
+            printf_calls.push_line(format!(
+                "      if ({ARRAY_INDEX_NAME} > 0) {PRINTF_MACRO_NAME}(\",\");"
+            ));
+            printf_calls.push_line(format!(
+                "      copar_print_struct_{}(&{value}[{ARRAY_INDEX_NAME}]);",
+                snake_case(struct_type)
+            ));
+            printf_calls.push_line(String::from("   }"));
+            printf_calls.push("]", None);
+            return;
+        }
+        _ => (),
+    }
+    if let Some((high_type, print_function)) = int128_printer(&member.member_type) {
+        parameters.push(format!("{high_type} {member_name}_high"));
+        parameters.push(format!("uint64_t {member_name}_low"));
+        printf_calls.push(&format!("{}(", member.member_type), None);
+        printf_calls.push_line(format!("   {print_function}({value}_high, {value}_low);"));
+        printf_calls.push(")", None);
+        return;
+    }
+    if let MemberType::Flags(flags_type) = &member.member_type {
+        // Each set flag is printed by name, the flags of lower bits being printed before
+        parameters.push(format!("uint32_t {member_name}"));
+        printf_calls.push("flags(", None);
+        printf_calls.push_line(format!(
+            "   if ({value} == 0) {PRINTF_MACRO_NAME}(\"{flags_type}\");"
+        ));
+        let flags = model
+            .defined_flags
+            .get(flags_type)
+            .map_or(&[][..], |flags| flags);
+        for (index, flag) in flags.iter().enumerate() {
+            let mask: u32 = 1 << index;
+            let printf_args = if index == 0 {
+                format!("\"{flags_type}::{flag}\"")
+            } else {
+                format!(
+                    "\"%s{flags_type}::{flag}\", ({value} & {:#x}) ? \"|\" : \"\"",
+                    mask - 1
+                )
+            };
+            printf_calls.push_line(format!(
+                "   if ({value} & {mask:#x}) {PRINTF_MACRO_NAME}({printf_args});"
+            ));
+        }
+        printf_calls.push(")", None);
+        return;
+    }
+    match array_element_type(&member.member_type) {
+        Some(element_type) if int128_printer(&element_type).is_some() => {
+            let (high_type, print_function) = int128_printer(&element_type).unwrap();
+            let words_type = if high_type == "int64_t" {
+                "copar_i128_words"
+            } else {
+                "copar_u128_words"
+            };
+            let element = format!("{value}[{ARRAY_INDEX_NAME}]");
+            parameters.push(format!("const struct {words_type} *{member_name}"));
+            parameters.push(format!("size_t {member_name}_len"));
+            printf_calls.push(&format!("{element_type}(["), None);
+            printf_calls.push_line(format!(
+                "   for (size_t {ARRAY_INDEX_NAME} = 0; {ARRAY_INDEX_NAME} < {value}_len; {ARRAY_INDEX_NAME}++) {{"
+            ));
+            printf_calls.push_line(format!(
+                "      if ({ARRAY_INDEX_NAME} > 0) {PRINTF_MACRO_NAME}(\",\");"
+            ));
+            printf_calls.push_line(format!(
+                "      {print_function}({element}.high, {element}.low);"
+            ));
+            printf_calls.push_line(String::from("   }"));
+            printf_calls.push("])", None);
+        }
+        Some(element_type) => {
+            let element = format!("{value}[{ARRAY_INDEX_NAME}]");
+            let (c_type, format, arg) = printf_value(&element_type, &element);
+            parameters.push(format!("const {c_type} *{member_name}"));
+            parameters.push(format!("size_t {member_name}_len"));
+            // The parser splits the arguments on spaces, the elements are only
+            // separated by commas
+            printf_calls.push(&format!("{element_type}(["), None);
+            printf_calls.push_line(format!(
+                "   for (size_t {ARRAY_INDEX_NAME} = 0; {ARRAY_INDEX_NAME} < {value}_len; {ARRAY_INDEX_NAME}++) {{"
+            ));
+            printf_calls.push_line(format!(
+                "      if ({ARRAY_INDEX_NAME} > 0) {PRINTF_MACRO_NAME}(\",\");"
+            ));
+            printf_calls.push_line(format!("      {PRINTF_MACRO_NAME}(\"{format}\", {arg});"));
+            printf_calls.push_line(String::from("   }"));
+            printf_calls.push("])", None);
+        }
+        None => {
+            let (c_type, format, arg) = printf_value(&member.member_type, &value);
+            let type_name = match &member.member_type {
+                MemberType::Identifier(_) => String::from("id"),
+                member_type => member_type.to_string(),
+            };
+            parameters.push(format!("{c_type} {member_name}"));
+            printf_calls.push(&format!("{type_name}({format})"), Some(arg));
+        }
+    }
+}
+
+/// Write the structure holding the value of a nested structure argument and the function
+/// printing it
+fn generate_c_emitter_struct(
+    model: &Model,
+    struct_type: &str,
+    struct_members: &[StructureDefinitionMember],
+    output_file: &mut impl std::io::Write,
+) {
+    let mut fields = Vec::new();
+    let mut printf_calls = PrintfCalls::default();
+    printf_calls.push(&format!("{struct_type}{{"), None);
+    for (index, member) in struct_members.iter().enumerate() {
+        let separator = if index == 0 { "" } else { " " };
+        printf_calls.push(&format!("{separator}{}: ", member.member_name), None);
+        push_c_emitter_member(
+            model,
+            member,
+            "copar_value->",
+            &mut fields,
+            &mut printf_calls,
+        );
+    }
+    printf_calls.push("}", None);
+    printf_calls.flush();
+
+    let struct_name = snake_case(struct_type);
+    writeln!(output_file, "struct copar_{struct_name}{{").unwrap();
+    for field in fields.iter() {
+        writeln!(output_file, "   {field};").unwrap();
+    }
+    writeln!(output_file, "}};").unwrap();
+    generate_blank_line(output_file);
+    writeln!(
+        output_file,
+        "static inline void copar_print_struct_{struct_name}(const struct copar_{struct_name} *copar_value)"
+    )
+    .unwrap();
+    writeln!(output_file, "{{").unwrap();
+    for line in printf_calls.lines.iter() {
+        writeln!(output_file, "{line}").unwrap();
+    }
+    writeln!(output_file, "}}").unwrap();
+}
+
+/// Write the function printing the record
+fn generate_c_emitter_function(
+    model: &Model,
+    record_name: &str,
+    record_members: &[StructureDefinitionMember],
+    output_file: &mut impl std::io::Write,
+) {
+    let (delimiter, logged_record_name) = if model.expectation_records.contains(record_name) {
+        ('?', record_name.strip_suffix(EXPECT_RECORD_SUFFIX).unwrap())
+    } else {
+        ('=', record_name)
+    };
+
+    let mut parameters = Vec::new();
+    let mut printf_calls = PrintfCalls::default();
+    printf_calls.push(&format!("#{delimiter} {logged_record_name}"), None);
+    for member in record_members.iter() {
+        printf_calls.push(&format!(" {}: ", member.member_name), None);
+        push_c_emitter_member(model, member, "", &mut parameters, &mut printf_calls);
+    }
+    printf_calls.push(&format!(" {delimiter}#\\n"), None);
+    printf_calls.flush();
+
+    let parameters = if parameters.is_empty() {
+        String::from("void")
+    } else {
+        parameters.join(", ")
+    };
+    writeln!(
+        output_file,
+        "static inline void copar_log_{}({parameters})",
+        snake_case(record_name)
+    )
+    .unwrap();
+    writeln!(output_file, "{{").unwrap();
+    for line in printf_calls.lines.iter() {
+        writeln!(output_file, "{line}").unwrap();
+    }
+    writeln!(output_file, "}}").unwrap();
+}
@@ -1,6 +1,8 @@
-use super::generate_blank_line;
+use super::{
+    fmt_source_comment, generate_blank_line, generate_phase_comments, generate_provenance_comment,
+};
 use crate::{
-    model::{ArrayInstanceVariant, OperationParameterVariant},
+    model::{ArrayInstanceVariant, OperationParameterVariant, Sequence, StructInstanceVariant},
     unirecord::MemberType,
     Model,
 };
@@ -18,9 +20,17 @@ mod private {
         fn generate_operation_variants_definition(&self, output_file: &mut impl std::io::Write);
         fn generate_operation_definition(&self, output_file: &mut impl std::io::Write);
         fn generate_enum_definitions(&self, output_file: &mut impl std::io::Write);
+        fn generate_int128_definitions(&self, output_file: &mut impl std::io::Write);
         fn generate_struct_definitions(&self, output_file: &mut impl std::io::Write);
-        fn generate_operation_list(&self, output_file: &mut impl std::io::Write);
+        fn generate_operation_list(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
+        fn generate_delay_list(&self, sequence: &Sequence, output_file: &mut impl std::io::Write);
+        fn generate_phase_defines(&self, output_file: &mut impl std::io::Write);
         fn generate_array_instances(&self, output_file: &mut impl std::io::Write);
+        fn generate_struct_instances(&self, output_file: &mut impl std::io::Write);
         fn generate_operation_instances(&self, output_file: &mut impl std::io::Write);
         fn generate_source_pre(&self, output_file: &mut impl std::io::Write);
         fn generate_source_post(&self, output_file: &mut impl std::io::Write);
@@ -60,7 +70,8 @@ impl CGeneration for Model {
 
 impl private::Sealed for Model {
     fn generate_header_pre(&self, output_file: &mut impl std::io::Write) {
-        let sequence_name = self.sequence_name.as_ref().unwrap().to_uppercase();
+        generate_provenance_comment(self, output_file);
+        let sequence_name = self.main_sequence_name().unwrap().to_uppercase();
         let sequence_name = sequence_name.as_str();
         write!(output_file, "#ifndef _{sequence_name}_H\n#define _{sequence_name}_H\n#include <stdint.h>\n#include <stdbool.h>\n").unwrap();
     }
@@ -100,14 +111,78 @@ impl private::Sealed for Model {
         for (enum_type_name, enum_members) in self.defined_enums.iter() {
             writeln!(output_file, "enum {enum_type_name}{{").unwrap();
             for enum_member in enum_members.iter() {
-                writeln!(output_file, "   {enum_type_name}{enum_member},").unwrap();
+                let member_name = &enum_member.name;
+                match enum_member.discriminant {
+                    Some(discriminant) => writeln!(
+                        output_file,
+                        "   {enum_type_name}{member_name} = {discriminant:#x},"
+                    ),
+                    None => writeln!(output_file, "   {enum_type_name}{member_name},"),
+                }
+                .unwrap();
             }
             writeln!(output_file, "}};").unwrap();
         }
+        // The masks are macros rather than enum constants, which do not go beyond INT_MAX
+        for (flags_type_name, flags) in self.defined_flags.iter() {
+            for (index, flag) in flags.iter().enumerate() {
+                let mask: u32 = 1 << index;
+                writeln!(output_file, "#define {flags_type_name}{flag} {mask:#x}u").unwrap();
+            }
+        }
+    }
+
+    fn generate_int128_definitions(&self, output_file: &mut impl std::io::Write) {
+        let members = self
+            .defined_structs
+            .values()
+            .chain(self.defined_records.values());
+        let uses_int128 = members.flatten().any(|member| {
+            matches!(
+                member.member_type,
+                MemberType::X128
+                    | MemberType::U128
+                    | MemberType::I128
+                    | MemberType::ArrayOfX128
+                    | MemberType::ArrayOfU128
+                    | MemberType::ArrayOfI128
+            )
+        });
+        if !uses_int128 {
+            return;
+        }
+        write!(
+            output_file,
+            "#ifdef COPAR_NATIVE_INT128
+typedef unsigned __int128 copar_u128;
+typedef __int128 copar_i128;
+#define COPAR_U128(high, low) ((((copar_u128)(high)) << 64) | (low))
+#define COPAR_I128(high, low) ((copar_i128)COPAR_U128(high, low))
+#else
+typedef struct{{
+   uint64_t low;
+   uint64_t high;
+}}copar_u128;
+typedef struct{{
+   uint64_t low;
+   int64_t high;
+}}copar_i128;
+#define COPAR_U128(high, low) {{(low), (high)}}
+#define COPAR_I128(high, low) {{(low), (int64_t)(high)}}
+#endif
+"
+        )
+        .unwrap();
+        generate_blank_line(output_file);
     }
 
     fn generate_struct_definitions(&self, output_file: &mut impl std::io::Write) {
-        for (struct_name, struct_members) in self.defined_records.iter() {
+        // The structure arguments are used by the records
+        let structures = self
+            .defined_structs
+            .iter()
+            .chain(self.defined_records.iter());
+        for (struct_name, struct_members) in structures {
             writeln!(output_file, "typedef struct{{").unwrap();
             for struct_member in struct_members {
                 let struct_member_name = struct_member.member_name.as_str();
@@ -120,28 +195,88 @@ impl private::Sealed for Model {
         }
     }
 
-    fn generate_operation_list(&self, output_file: &mut impl std::io::Write) {
-        writeln!(
-            output_file,
-            "const Operation {}[] = {{",
-            self.sequence_name.as_ref().unwrap()
-        )
-        .unwrap();
+    fn generate_operation_list(&self, sequence: &Sequence, output_file: &mut impl std::io::Write) {
+        writeln!(output_file, "const Operation {}[] = {{", sequence.name).unwrap();
 
-        let nb_operations = self.operation_ref_table.len();
-        for (index, operation_table_member) in self.operation_ref_table.iter().enumerate() {
+        let nb_operations = sequence.operation_ref_table.len();
+        let phase_markers = sequence.phase_markers();
+        let mut phase_markers = phase_markers.iter().peekable();
+        for (index, operation_table_member) in sequence.operation_ref_table.iter().enumerate() {
+            generate_phase_comments(&mut phase_markers, Some(index), "   ", output_file);
             let record_name = macro_case(&operation_table_member.operation_type);
             let operation_id = format!("OPERATION_ID_{}", record_name);
             let operation_variant_instance_ref =
                 operation_table_member.operation_variant_ref_name.as_str();
             let operation_variant_name = snake_case(&operation_table_member.operation_type);
             write!(output_file, "   {{.id = {operation_id}, .variant={{.{operation_variant_name}=&{operation_variant_instance_ref}}}}}",).unwrap();
-            if index < nb_operations - 1 {
-                writeln!(output_file, ",").unwrap();
+            let is_last = index == nb_operations - 1;
+            if !is_last {
+                write!(output_file, ",").unwrap();
+            }
+            write!(
+                output_file,
+                "{}",
+                fmt_source_comment(self, operation_table_member)
+            )
+            .unwrap();
+            if !is_last {
+                generate_blank_line(output_file);
             }
         }
+        generate_blank_line(output_file);
+        generate_phase_comments(&mut phase_markers, None, "   ", output_file);
 
-        write!(output_file, "\n}};\n",).unwrap();
+        writeln!(output_file, "}};",).unwrap();
+        generate_blank_line(output_file);
+    }
+
+    fn generate_phase_defines(&self, output_file: &mut impl std::io::Write) {
+        let mut has_phases = false;
+        for sequence in self.sequences.iter() {
+            for phase_range in sequence.phase_ranges() {
+                let path: Vec<String> = phase_range
+                    .path
+                    .iter()
+                    .map(|name| macro_case(name))
+                    .collect();
+                let define_name =
+                    format!("{}_PHASE_{}", macro_case(&sequence.name), path.join("_"));
+                writeln!(
+                    output_file,
+                    "#define {}_START {}",
+                    define_name, phase_range.start
+                )
+                .unwrap();
+                writeln!(
+                    output_file,
+                    "#define {}_END {}",
+                    define_name, phase_range.end
+                )
+                .unwrap();
+                has_phases = true;
+            }
+        }
+        if has_phases {
+            generate_blank_line(output_file);
+        }
+    }
+
+    fn generate_delay_list(&self, sequence: &Sequence, output_file: &mut impl std::io::Write) {
+        if !sequence.has_timing() {
+            return;
+        }
+        let delays: Vec<u32> = sequence
+            .operation_ref_table
+            .iter()
+            .map(|operation_table_member| operation_table_member.delay_us)
+            .collect();
+        writeln!(
+            output_file,
+            "const uint32_t {}_delays_us[] = {};",
+            sequence.name,
+            Self::fmt_c_array_value(&delays, |element| format!("{}", element))
+        )
+        .unwrap();
         generate_blank_line(output_file);
     }
 
@@ -157,6 +292,37 @@ impl private::Sealed for Model {
         generate_blank_line(output_file);
     }
 
+    fn generate_struct_instances(&self, output_file: &mut impl std::io::Write) {
+        if self.struct_instances.is_empty() {
+            return;
+        }
+        for (struct_instance, struct_instance_name) in self.struct_instances.iter() {
+            match struct_instance {
+                StructInstanceVariant::Struct {
+                    struct_type,
+                    fields,
+                } => {
+                    let fields: Vec<String> = fields.iter().map(Self::fmt_struct_member).collect();
+                    writeln!(
+                        output_file,
+                        "const {struct_type} {struct_instance_name} = {{{}}};",
+                        fields.join(", ")
+                    )
+                }
+                StructInstanceVariant::Array {
+                    struct_type,
+                    instances,
+                } => writeln!(
+                    output_file,
+                    "const {struct_type}* const {struct_instance_name}[] = {};",
+                    Self::fmt_c_array_value(instances, |instance| format!("&{instance}"))
+                ),
+            }
+            .unwrap();
+        }
+        generate_blank_line(output_file);
+    }
+
     fn generate_operation_instances(&self, output_file: &mut impl std::io::Write) {
         for (operation, operation_instance_name) in self.operation_instances.iter() {
             let operation_type = operation.operation_type.as_str();
@@ -181,25 +347,34 @@ impl private::Sealed for Model {
     }
 
     fn generate_source_pre(&self, output_file: &mut impl std::io::Write) {
+        generate_provenance_comment(self, output_file);
         writeln!(output_file, "#include \"playdisc.h\"").unwrap();
         generate_blank_line(output_file);
     }
 
     fn generate_source_post(&self, output_file: &mut impl std::io::Write) {
-        let sequence_name = self.sequence_name.as_ref().unwrap().to_lowercase();
-        write!(
-            output_file,
-            "const uint32_t {}_len = sizeof({}_operations)/sizeof(Operation);",
-            sequence_name, sequence_name
-        )
-        .unwrap();
+        for (index, sequence) in self.sequences.iter().enumerate() {
+            if index > 0 {
+                generate_blank_line(output_file);
+            }
+            write!(
+                output_file,
+                "const uint32_t {}_len = sizeof({})/sizeof(Operation);",
+                sequence.name, sequence.name
+            )
+            .unwrap();
+        }
     }
 
     fn generate_c_source(&self, output_file: &mut impl std::io::Write) {
         self.generate_source_pre(output_file);
         self.generate_array_instances(output_file);
+        self.generate_struct_instances(output_file);
         self.generate_operation_instances(output_file);
-        self.generate_operation_list(output_file);
+        for sequence in self.sequences.iter() {
+            self.generate_operation_list(sequence, output_file);
+            self.generate_delay_list(sequence, output_file);
+        }
         self.generate_source_post(output_file);
     }
 
@@ -210,11 +385,13 @@ impl private::Sealed for Model {
         generate_blank_line(output_file);
         self.generate_enum_definitions(output_file);
         generate_blank_line(output_file);
+        self.generate_int128_definitions(output_file);
         self.generate_struct_definitions(output_file);
         self.generate_operation_variants_definition(output_file);
         generate_blank_line(output_file);
         self.generate_operation_definition(output_file);
         generate_blank_line(output_file);
+        self.generate_phase_defines(output_file);
         self.generate_header_post(output_file);
     }
 
@@ -224,34 +401,46 @@ impl private::Sealed for Model {
             MemberType::X16 => "uint16_t",
             MemberType::X32 => "uint32_t",
             MemberType::X64 => "uint64_t",
+            MemberType::X128 => "copar_u128",
             MemberType::U8 => "uint8_t",
             MemberType::U16 => "uint8_t",
             MemberType::U32 => "uint32_t",
             MemberType::U64 => "uint64_t",
+            MemberType::U128 => "copar_u128",
             MemberType::I8 => "int8_t",
             MemberType::I16 => "int16_t",
             MemberType::I32 => "int32_t",
             MemberType::I64 => "int64_t",
+            MemberType::I128 => "copar_i128",
             MemberType::F32 => "float",
             MemberType::F64 => "double",
             MemberType::ArrayOfX8 => "uint8_t* const",
             MemberType::ArrayOfX16 => "uint16_t* const",
             MemberType::ArrayOfX32 => "uint32_t* const",
             MemberType::ArrayOfX64 => "uint64_t* const",
+            MemberType::ArrayOfX128 => "copar_u128* const",
             MemberType::ArrayOfU8 => "uint8_t* const",
             MemberType::ArrayOfU16 => "uint16_t* const",
             MemberType::ArrayOfU32 => "uint32_t* const",
             MemberType::ArrayOfU64 => "uint64_t* const",
+            MemberType::ArrayOfU128 => "copar_u128* const",
             MemberType::ArrayOfI8 => "int8_t* const",
             MemberType::ArrayOfI16 => "int16_t* const",
             MemberType::ArrayOfI32 => "int32_t* const",
             MemberType::ArrayOfI64 => "int64_t* const",
+            MemberType::ArrayOfI128 => "copar_i128* const",
             MemberType::ArrayOfF32 => "float* const",
             MemberType::ArrayOfF64 => "double* const",
             MemberType::Bool => "bool",
             MemberType::Identifier(enum_type) => {
                 return "const enum ".to_string() + enum_type.as_str()
             }
+            // Flags are ORed masks, not values of their enum
+            MemberType::Flags(_) => "uint32_t",
+            MemberType::Struct(struct_type) => return format!("const {struct_type}* const"),
+            MemberType::ArrayOfStruct(struct_type) => {
+                return format!("const {struct_type}* const* const")
+            }
         };
         String::from("const ") + ret
     }
@@ -328,6 +517,14 @@ impl private::Sealed for Model {
                 array_value = Self::fmt_c_array_value(array, |element| format!("{}", element));
                 array_type = "int64_t";
             }
+            ArrayInstanceVariant::X128(array) | ArrayInstanceVariant::U128(array) => {
+                array_value = Self::fmt_c_array_value(array, |element| fmt_c_u128(*element));
+                array_type = "copar_u128";
+            }
+            ArrayInstanceVariant::I128(array) => {
+                array_value = Self::fmt_c_array_value(array, |element| fmt_c_i128(*element));
+                array_type = "copar_i128";
+            }
             _ => unimplemented!(),
         }
         format!("const {} {}[] = {};", array_type, array_name, array_value)
@@ -371,6 +568,12 @@ impl private::Sealed for Model {
             OperationParameterVariant::I64(param) => {
                 format!(".{} = {}", param.name, param.value)
             }
+            OperationParameterVariant::X128(param) | OperationParameterVariant::U128(param) => {
+                format!(".{} = {}", param.name, fmt_c_u128(param.value))
+            }
+            OperationParameterVariant::I128(param) => {
+                format!(".{} = {}", param.name, fmt_c_i128(param.value))
+            }
             OperationParameterVariant::F32(param) => {
                 format!(".{} = {}", param.name, param.value)
             }
@@ -413,6 +616,11 @@ impl private::Sealed for Model {
             OperationParameterVariant::ArrayOfI64(param) => {
                 format!(".{} = {}", param.name, param.value)
             }
+            OperationParameterVariant::ArrayOfX128(param)
+            | OperationParameterVariant::ArrayOfU128(param)
+            | OperationParameterVariant::ArrayOfI128(param) => {
+                format!(".{} = {}", param.name, param.value)
+            }
             OperationParameterVariant::ArrayOfF32(param) => {
                 format!(".{} = {}", param.name, param.value)
             }
@@ -431,6 +639,35 @@ impl private::Sealed for Model {
                 let val = param.enum_type.clone() + param.value.as_str();
                 format!(".{} = {}", param.name, val)
             }
+            OperationParameterVariant::Flags(param) => {
+                let val = if param.flags.is_empty() {
+                    String::from("0")
+                } else {
+                    param
+                        .flags
+                        .iter()
+                        .map(|flag| param.flags_type.clone() + flag)
+                        .collect::<Vec<String>>()
+                        .join(" | ")
+                };
+                format!(".{} = {}", param.name, val)
+            }
+            OperationParameterVariant::Struct(param) => {
+                format!(".{} = &{}", param.name, param.value)
+            }
+            OperationParameterVariant::ArrayOfStruct(param) => {
+                format!(".{} = {}", param.name, param.value)
+            }
         }
     }
 }
+
+/// C has no 128-bit literals, values are split in two 64-bit words
+fn fmt_c_u128(value: u128) -> String {
+    format!("COPAR_U128(0x{:x}, 0x{:x})", value >> 64, value as u64)
+}
+
+fn fmt_c_i128(value: i128) -> String {
+    let value = value as u128;
+    format!("COPAR_I128(0x{:x}, 0x{:x})", value >> 64, value as u64)
+}
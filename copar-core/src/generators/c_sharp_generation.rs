@@ -1,6 +1,8 @@
-use super::generate_blank_line;
+use super::{
+    fmt_source_comment, generate_blank_line, generate_phase_comments, generate_provenance_comment,
+};
 use crate::{
-    model::{ArrayInstanceVariant, OperationParameterVariant},
+    model::{ArrayInstanceVariant, OperationParameterVariant, Sequence, StructInstanceVariant},
     unirecord::MemberType,
     Model,
 };
@@ -26,8 +28,23 @@ mod private {
         fn generate_cs_enums(&self, output_file: &mut impl std::io::Write);
         fn generate_cs_structs(&self, output_file: &mut impl std::io::Write);
         fn generate_cs_arrays(&self, output_file: &mut impl std::io::Write);
+        fn generate_cs_struct_instances(&self, output_file: &mut impl std::io::Write);
         fn generate_cs_instances(&self, output_file: &mut impl std::io::Write);
-        fn generate_cs_operation_list(&self, output_file: &mut impl std::io::Write);
+        fn generate_cs_operation_list(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
+        fn generate_cs_phase_ranges(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
+        fn generate_cs_delay_list(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
         fn member_type_to_cs_type_string(member_type: &MemberType) -> String;
         fn fmt_cs_array_value<T, F: Fn(&T) -> String>(array: &[T], format_function: F) -> String;
         fn fmt_cs_array_instance(
@@ -54,6 +71,7 @@ impl CSharpGeneration for Model {
 
 impl private::Sealed for Model {
     fn generate_cs_file(&self, output_file: &mut impl std::io::Write) {
+        generate_provenance_comment(self, output_file);
         self.generate_cs_namespace_open(output_file);
 
         self.generate_cs_operation_id_enum(output_file);
@@ -62,14 +80,22 @@ impl private::Sealed for Model {
         self.generate_cs_enums(output_file);
         self.generate_cs_structs(output_file);
 
-        let base_class_name = self.sequence_name.as_ref().map_or("Playdisc", |s| s);
+        let base_class_name = self.main_sequence_name().map_or("Playdisc", |s| s);
         let static_class_name = format!("{}Constants", pascal_case(base_class_name));
 
         self.generate_cs_static_class_open(output_file, &static_class_name);
 
         self.generate_cs_arrays(output_file);
+        self.generate_cs_struct_instances(output_file);
         self.generate_cs_instances(output_file);
-        self.generate_cs_operation_list(output_file);
+        for (index, sequence) in self.sequences.iter().enumerate() {
+            if index > 0 {
+                generate_blank_line(output_file);
+            }
+            self.generate_cs_operation_list(sequence, output_file);
+            self.generate_cs_delay_list(sequence, output_file);
+            self.generate_cs_phase_ranges(sequence, output_file);
+        }
 
         self.generate_cs_static_class_close(output_file);
 
@@ -77,7 +103,7 @@ impl private::Sealed for Model {
     }
 
     fn generate_cs_namespace_open(&self, output_file: &mut impl std::io::Write) {
-        let namespace = self.sequence_name.as_ref().map_or_else(
+        let namespace = self.main_sequence_name().map_or_else(
             || "GeneratedPlaydisc".to_string(),
             |s_name| format!("Generated{}", pascal_case(s_name)),
         );
@@ -132,9 +158,35 @@ impl private::Sealed for Model {
 
     fn generate_cs_enums(&self, output_file: &mut impl std::io::Write) {
         for (enum_type_name, enum_members) in self.defined_enums.iter() {
-            writeln!(output_file, "    public enum {} {{", enum_type_name).unwrap();
+            // Explicit discriminants are 32 bits unsigned values
+            if enum_members
+                .iter()
+                .any(|member| member.discriminant.is_some())
+            {
+                writeln!(output_file, "    public enum {} : uint {{", enum_type_name).unwrap();
+            } else {
+                writeln!(output_file, "    public enum {} {{", enum_type_name).unwrap();
+            }
             for enum_member in enum_members.iter() {
-                writeln!(output_file, "        {},", enum_member).unwrap();
+                match enum_member.discriminant {
+                    Some(discriminant) => writeln!(
+                        output_file,
+                        "        {} = {:#x},",
+                        enum_member.name, discriminant
+                    ),
+                    None => writeln!(output_file, "        {},", enum_member.name),
+                }
+                .unwrap();
+            }
+            writeln!(output_file, "    }}").unwrap();
+            generate_blank_line(output_file);
+        }
+        for (flags_type_name, flags) in self.defined_flags.iter() {
+            writeln!(output_file, "    [System.Flags]").unwrap();
+            writeln!(output_file, "    public enum {} : uint {{", flags_type_name).unwrap();
+            for (index, flag) in flags.iter().enumerate() {
+                let mask: u32 = 1 << index;
+                writeln!(output_file, "        {} = {:#x},", flag, mask).unwrap();
             }
             writeln!(output_file, "    }}").unwrap();
             generate_blank_line(output_file);
@@ -142,7 +194,11 @@ impl private::Sealed for Model {
     }
 
     fn generate_cs_structs(&self, output_file: &mut impl std::io::Write) {
-        for (struct_name, struct_members) in self.defined_records.iter() {
+        let structures = self
+            .defined_structs
+            .iter()
+            .chain(self.defined_records.iter());
+        for (struct_name, struct_members) in structures {
             writeln!(output_file, "    public struct {} {{", struct_name).unwrap();
             for struct_member in struct_members {
                 let member_name = &struct_member.member_name;
@@ -175,6 +231,39 @@ impl private::Sealed for Model {
         }
     }
 
+    fn generate_cs_struct_instances(&self, output_file: &mut impl std::io::Write) {
+        // Static fields are initialized in order, the referenced instances coming first
+        for (struct_instance, struct_instance_name) in self.struct_instances.iter() {
+            let csharp_instance_name = pascal_case(struct_instance_name);
+            match struct_instance {
+                StructInstanceVariant::Struct {
+                    struct_type,
+                    fields,
+                } => {
+                    let fields: Vec<String> =
+                        fields.iter().map(Self::fmt_cs_struct_member).collect();
+                    writeln!(
+                        output_file,
+                        "        public static readonly {struct_type} {csharp_instance_name} = new {struct_type} {{ {} }};",
+                        fields.join(", ")
+                    )
+                }
+                StructInstanceVariant::Array {
+                    struct_type,
+                    instances,
+                } => writeln!(
+                    output_file,
+                    "        public static readonly {struct_type}[] {csharp_instance_name} =  {};",
+                    Self::fmt_cs_array_value(instances, |instance| pascal_case(instance))
+                ),
+            }
+            .unwrap();
+        }
+        if !self.struct_instances.is_empty() {
+            generate_blank_line(output_file);
+        }
+    }
+
     fn generate_cs_instances(&self, output_file: &mut impl std::io::Write) {
         for (operation, operation_instance_name) in self.operation_instances.iter() {
             let operation_type = operation.operation_type.as_str();
@@ -202,8 +291,12 @@ impl private::Sealed for Model {
         }
     }
 
-    fn generate_cs_operation_list(&self, output_file: &mut impl std::io::Write) {
-        let array_name = pascal_case(self.sequence_name.as_ref().unwrap());
+    fn generate_cs_operation_list(
+        &self,
+        sequence: &Sequence,
+        output_file: &mut impl std::io::Write,
+    ) {
+        let array_name = pascal_case(&sequence.name);
 
         writeln!(
             output_file,
@@ -212,45 +305,111 @@ impl private::Sealed for Model {
         )
         .unwrap();
 
-        for op_ref in self.operation_ref_table.iter() {
+        let phase_markers = sequence.phase_markers();
+        let mut phase_markers = phase_markers.iter().peekable();
+        for (index, op_ref) in sequence.operation_ref_table.iter().enumerate() {
+            generate_phase_comments(&mut phase_markers, Some(index), "            ", output_file);
             let operation_id_member = &op_ref.operation_type;
             let csharp_instance_name = pascal_case(&op_ref.operation_variant_ref_name);
 
             writeln!(
                 output_file,
-                "            new Operation(OperationId.{}, {}),",
-                operation_id_member, csharp_instance_name
+                "            new Operation(OperationId.{}, {}),{}",
+                operation_id_member,
+                csharp_instance_name,
+                fmt_source_comment(self, op_ref)
             )
             .unwrap();
         }
+        generate_phase_comments(&mut phase_markers, None, "            ", output_file);
 
         writeln!(output_file, "        }};").unwrap();
     }
 
+    fn generate_cs_phase_ranges(&self, sequence: &Sequence, output_file: &mut impl std::io::Write) {
+        let phase_ranges = sequence.phase_ranges();
+        if phase_ranges.is_empty() {
+            return;
+        }
+
+        generate_blank_line(output_file);
+        for phase_range in phase_ranges.iter() {
+            let constant_name = format!(
+                "{}Phase{}",
+                pascal_case(&sequence.name),
+                phase_range
+                    .path
+                    .iter()
+                    .map(|name| pascal_case(name))
+                    .collect::<String>()
+            );
+            writeln!(
+                output_file,
+                "        public const int {}Start = {};",
+                constant_name, phase_range.start
+            )
+            .unwrap();
+            writeln!(
+                output_file,
+                "        public const int {}End = {};",
+                constant_name, phase_range.end
+            )
+            .unwrap();
+        }
+    }
+
+    fn generate_cs_delay_list(&self, sequence: &Sequence, output_file: &mut impl std::io::Write) {
+        if !sequence.has_timing() {
+            return;
+        }
+        let array_name = pascal_case(&sequence.name) + "DelaysUs";
+        let delays: Vec<u32> = sequence
+            .operation_ref_table
+            .iter()
+            .map(|op_ref| op_ref.delay_us)
+            .collect();
+
+        generate_blank_line(output_file);
+        writeln!(
+            output_file,
+            "        public static readonly uint[] {} = {};",
+            array_name,
+            Self::fmt_cs_array_value(&delays, |e| format!("{e}"))
+        )
+        .unwrap();
+    }
+
     fn member_type_to_cs_type_string(member_type: &MemberType) -> String {
         match member_type {
             MemberType::X8 | MemberType::U8 => "byte".to_string(),
             MemberType::X16 | MemberType::U16 => "ushort".to_string(),
             MemberType::X32 | MemberType::U32 => "uint".to_string(),
             MemberType::X64 | MemberType::U64 => "ulong".to_string(),
+            MemberType::X128 | MemberType::U128 => "UInt128".to_string(),
             MemberType::I8 => "sbyte".to_string(),
             MemberType::I16 => "short".to_string(),
             MemberType::I32 => "int".to_string(),
             MemberType::I64 => "long".to_string(),
+            MemberType::I128 => "Int128".to_string(),
             MemberType::F32 => "float".to_string(),
             MemberType::F64 => "double".to_string(),
             MemberType::ArrayOfX8 | MemberType::ArrayOfU8 => "byte[]".to_string(),
             MemberType::ArrayOfX16 | MemberType::ArrayOfU16 => "ushort[]".to_string(),
             MemberType::ArrayOfX32 | MemberType::ArrayOfU32 => "uint[]".to_string(),
             MemberType::ArrayOfX64 | MemberType::ArrayOfU64 => "ulong[]".to_string(),
+            MemberType::ArrayOfX128 | MemberType::ArrayOfU128 => "UInt128[]".to_string(),
             MemberType::ArrayOfI8 => "sbyte[]".to_string(),
             MemberType::ArrayOfI16 => "short[]".to_string(),
             MemberType::ArrayOfI32 => "int[]".to_string(),
             MemberType::ArrayOfI64 => "long[]".to_string(),
+            MemberType::ArrayOfI128 => "Int128[]".to_string(),
             MemberType::ArrayOfF32 => "float[]".to_string(),
             MemberType::ArrayOfF64 => "double[]".to_string(),
             MemberType::Bool => "bool".to_string(),
             MemberType::Identifier(enum_type) => enum_type.clone(),
+            MemberType::Flags(flags_type) => flags_type.clone(),
+            MemberType::Struct(struct_type) => struct_type.clone(),
+            MemberType::ArrayOfStruct(struct_type) => format!("{struct_type}[]"),
         }
     }
 
@@ -301,6 +460,14 @@ impl private::Sealed for Model {
             ArrayInstanceVariant::U64(array) => {
                 (Self::fmt_cs_array_value(array, |e| format!("{e}")), "ulong")
             }
+            ArrayInstanceVariant::X128(array) => (
+                Self::fmt_cs_array_value(array, |e| fmt_cs_u128(*e, true)),
+                "UInt128",
+            ),
+            ArrayInstanceVariant::U128(array) => (
+                Self::fmt_cs_array_value(array, |e| fmt_cs_u128(*e, false)),
+                "UInt128",
+            ),
             ArrayInstanceVariant::I8(array) => {
                 (Self::fmt_cs_array_value(array, |e| format!("{e}")), "sbyte")
             }
@@ -313,6 +480,10 @@ impl private::Sealed for Model {
             ArrayInstanceVariant::I64(array) => {
                 (Self::fmt_cs_array_value(array, |e| format!("{e}")), "long")
             }
+            ArrayInstanceVariant::I128(array) => (
+                Self::fmt_cs_array_value(array, |e| fmt_cs_i128(*e)),
+                "Int128",
+            ),
             _ => {
                 unimplemented!("Array type in ArrayInstanceVariant not supported for C# generation")
             }
@@ -340,6 +511,15 @@ impl private::Sealed for Model {
             }
             OperationParameterVariant::U64(param) => format!("{} = {}", param.name, param.value),
             OperationParameterVariant::I64(param) => format!("{} = {}", param.name, param.value),
+            OperationParameterVariant::X128(param) => {
+                format!("{} = {}", param.name, fmt_cs_u128(param.value, true))
+            }
+            OperationParameterVariant::U128(param) => {
+                format!("{} = {}", param.name, fmt_cs_u128(param.value, false))
+            }
+            OperationParameterVariant::I128(param) => {
+                format!("{} = {}", param.name, fmt_cs_i128(param.value))
+            }
             OperationParameterVariant::F32(param) => format!("{} = {}f", param.name, param.value),
             OperationParameterVariant::F64(param) => format!("{} = {}", param.name, param.value),
             OperationParameterVariant::ArrayOfX8(param)
@@ -354,6 +534,9 @@ impl private::Sealed for Model {
             | OperationParameterVariant::ArrayOfX64(param)
             | OperationParameterVariant::ArrayOfU64(param)
             | OperationParameterVariant::ArrayOfI64(param)
+            | OperationParameterVariant::ArrayOfX128(param)
+            | OperationParameterVariant::ArrayOfU128(param)
+            | OperationParameterVariant::ArrayOfI128(param)
             | OperationParameterVariant::ArrayOfF32(param)
             | OperationParameterVariant::ArrayOfF64(param) => {
                 let csharp_array_ref_name = pascal_case(&param.value);
@@ -366,6 +549,44 @@ impl private::Sealed for Model {
             OperationParameterVariant::Identifier(param) => {
                 format!("{} = {}.{}", param.name, param.enum_type, param.value)
             }
+            OperationParameterVariant::Flags(param) => {
+                // The literal 0 converts to any enum
+                let val_str = if param.flags.is_empty() {
+                    String::from("0")
+                } else {
+                    param
+                        .flags
+                        .iter()
+                        .map(|flag| format!("{}.{}", param.flags_type, flag))
+                        .collect::<Vec<String>>()
+                        .join(" | ")
+                };
+                format!("{} = {}", param.name, val_str)
+            }
+            OperationParameterVariant::Struct(param)
+            | OperationParameterVariant::ArrayOfStruct(param) => {
+                format!("{} = {}", param.name, pascal_case(&param.value))
+            }
         }
     }
 }
+
+/// C# has no 128-bit literals, values out of the 64-bit range are built from their two words
+fn fmt_cs_u128(value: u128, hexadecimal: bool) -> String {
+    match u64::try_from(value) {
+        Ok(value) if hexadecimal => format!("0x{value:x}"),
+        Ok(value) => format!("{value}"),
+        Err(_) => format!("new UInt128(0x{:x}, 0x{:x})", value >> 64, value as u64),
+    }
+}
+
+fn fmt_cs_i128(value: i128) -> String {
+    match i64::try_from(value) {
+        Ok(value) => format!("{value}"),
+        Err(_) => format!(
+            "new Int128(0x{:x}, 0x{:x})",
+            (value as u128) >> 64,
+            value as u64
+        ),
+    }
+}
@@ -0,0 +1,51 @@
+pub mod c_emitter_generation;
+pub mod c_generation;
+pub mod c_sharp_generation;
+pub mod rust_generation;
+
+use crate::model::OperationTableMember;
+use crate::Model;
+
+pub(crate) fn generate_blank_line(output_file: &mut impl std::io::Write) {
+    writeln!(output_file).unwrap();
+}
+
+/// Write the comment giving the origin of the generated file, followed by a blank line
+pub(crate) fn generate_provenance_comment(model: &Model, output_file: &mut impl std::io::Write) {
+    for line in model.provenance_lines() {
+        writeln!(output_file, "// {line}").unwrap();
+    }
+    generate_blank_line(output_file);
+}
+
+/// Trailing comment giving the log line of a table entry (eg. ` // init.log:1234 Cmd52`), empty
+/// when the source annotations are disabled
+pub(crate) fn fmt_source_comment(model: &Model, operation: &OperationTableMember) -> String {
+    let Some(source) = operation
+        .source
+        .as_ref()
+        .filter(|_| model.source_annotations)
+    else {
+        return String::new();
+    };
+    let record_name = &operation.operation_type;
+    match model.source_file_name.as_ref() {
+        Some(file_name) => format!(" // {file_name}:{} {record_name}", source.line_nb),
+        None => format!(" // line {} {record_name}", source.line_nb),
+    }
+}
+
+/// Write the phase marker comments placed before the table entry `index`, or every remaining
+/// marker when `index` is `None`.
+pub(crate) fn generate_phase_comments<'a>(
+    phase_markers: &mut std::iter::Peekable<impl Iterator<Item = &'a (usize, String)>>,
+    index: Option<usize>,
+    indent: &str,
+    output_file: &mut impl std::io::Write,
+) {
+    while let Some((_, comment)) =
+        phase_markers.next_if(|(marker_index, _)| index.is_none_or(|index| *marker_index == index))
+    {
+        writeln!(output_file, "{}// {}", indent, comment).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
-use super::generate_blank_line;
+use super::{
+    fmt_source_comment, generate_blank_line, generate_phase_comments, generate_provenance_comment,
+};
 use crate::{
-    model::{ArrayInstanceVariant, OperationParameterVariant},
+    model::{ArrayInstanceVariant, OperationParameterVariant, Sequence, StructInstanceVariant},
     unirecord::MemberType,
     Model,
 };
@@ -16,8 +18,23 @@ mod private {
         fn generate_rust_enums(&self, output_file: &mut impl std::io::Write);
         fn generate_rust_structs(&self, output_file: &mut impl std::io::Write);
         fn generate_rust_arrays(&self, output_file: &mut impl std::io::Write);
+        fn generate_rust_struct_instances(&self, output_file: &mut impl std::io::Write);
         fn generate_rust_instances(&self, output_file: &mut impl std::io::Write);
-        fn generate_rust_operation_list(&self, output_file: &mut impl std::io::Write);
+        fn generate_rust_operation_list(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
+        fn generate_rust_phase_ranges(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
+        fn generate_rust_delay_list(
+            &self,
+            sequence: &Sequence,
+            output_file: &mut impl std::io::Write,
+        );
         fn member_type_to_rust_type_string(member_type: &MemberType) -> String;
         fn fmt_rust_array_value<T, F: Fn(&T) -> String>(array: &[T], format_function: F) -> String;
         fn fmt_rust_array_instance(
@@ -47,6 +64,7 @@ impl RustGeneration for Model {
 
 impl private::Sealed for Model {
     fn generate_rust_file(&self, output_file: &mut impl std::io::Write) {
+        generate_provenance_comment(self, output_file);
         self.generate_rust_operation_id_enum(output_file);
         self.generate_rust_operation_variant_enum(output_file);
 
@@ -54,8 +72,17 @@ impl private::Sealed for Model {
         self.generate_rust_structs(output_file);
 
         self.generate_rust_arrays(output_file);
+        self.generate_rust_struct_instances(output_file);
         self.generate_rust_instances(output_file);
-        self.generate_rust_operation_list(output_file);
+        for (index, sequence) in self.sequences.iter().enumerate() {
+            if index > 0 {
+                generate_blank_line(output_file);
+                generate_blank_line(output_file);
+            }
+            self.generate_rust_operation_list(sequence, output_file);
+            self.generate_rust_delay_list(sequence, output_file);
+            self.generate_rust_phase_ranges(sequence, output_file);
+        }
     }
 
     fn generate_rust_operation_id_enum(&self, output_file: &mut impl std::io::Write) {
@@ -87,9 +114,42 @@ impl private::Sealed for Model {
     fn generate_rust_enums(&self, output_file: &mut impl std::io::Write) {
         for (enum_type_name, enum_members) in self.defined_enums.iter() {
             writeln!(output_file, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+            // Explicit discriminants are 32 bits unsigned values
+            if enum_members
+                .iter()
+                .any(|member| member.discriminant.is_some())
+            {
+                writeln!(output_file, "#[repr(u32)]").unwrap();
+            }
             writeln!(output_file, "pub enum {} {{", pascal_case(enum_type_name)).unwrap();
             for enum_member in enum_members.iter() {
-                writeln!(output_file, "    {},", pascal_case(enum_member)).unwrap();
+                let member_name = pascal_case(&enum_member.name);
+                match enum_member.discriminant {
+                    Some(discriminant) => {
+                        writeln!(output_file, "    {member_name} = {discriminant:#x},")
+                    }
+                    None => writeln!(output_file, "    {member_name},"),
+                }
+                .unwrap();
+            }
+            writeln!(output_file, "}}").unwrap();
+            generate_blank_line(output_file);
+        }
+        // Flags are masks named by associated consts, as with the bitflags crate
+        for (flags_type_name, flags) in self.defined_flags.iter() {
+            let flags_type_name = pascal_case(flags_type_name);
+            writeln!(output_file, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+            writeln!(output_file, "pub struct {flags_type_name}(pub u32);").unwrap();
+            generate_blank_line(output_file);
+            writeln!(output_file, "impl {flags_type_name} {{").unwrap();
+            for (index, flag) in flags.iter().enumerate() {
+                let mask: u32 = 1 << index;
+                writeln!(
+                    output_file,
+                    "    pub const {}: {flags_type_name} = {flags_type_name}({mask:#x});",
+                    macro_case(flag)
+                )
+                .unwrap();
             }
             writeln!(output_file, "}}").unwrap();
             generate_blank_line(output_file);
@@ -97,7 +157,11 @@ impl private::Sealed for Model {
     }
 
     fn generate_rust_structs(&self, output_file: &mut impl std::io::Write) {
-        for (struct_name, struct_members) in self.defined_records.iter() {
+        let structures = self
+            .defined_structs
+            .iter()
+            .chain(self.defined_records.iter());
+        for (struct_name, struct_members) in structures {
             writeln!(output_file, "#[derive(Debug, Clone, PartialEq)]").unwrap();
             writeln!(output_file, "pub struct {} {{", pascal_case(struct_name)).unwrap();
             for struct_member in struct_members {
@@ -125,6 +189,43 @@ impl private::Sealed for Model {
         }
     }
 
+    fn generate_rust_struct_instances(&self, output_file: &mut impl std::io::Write) {
+        for (struct_instance, struct_instance_name) in self.struct_instances.iter() {
+            let rust_instance_name = macro_case(struct_instance_name);
+            match struct_instance {
+                StructInstanceVariant::Struct {
+                    struct_type,
+                    fields,
+                } => {
+                    let struct_type = pascal_case(struct_type);
+                    let fields: Vec<String> =
+                        fields.iter().map(Self::fmt_rust_struct_member).collect();
+                    writeln!(
+                        output_file,
+                        "pub static {rust_instance_name}: {struct_type} = {struct_type} {{ {} }};",
+                        fields.join(", ")
+                    )
+                }
+                StructInstanceVariant::Array {
+                    struct_type,
+                    instances,
+                } => writeln!(
+                    output_file,
+                    "pub static {rust_instance_name}: &[&{}] = &{};",
+                    pascal_case(struct_type),
+                    Self::fmt_rust_array_value(instances, |instance| format!(
+                        "&{}",
+                        macro_case(instance)
+                    ))
+                ),
+            }
+            .unwrap();
+        }
+        if !self.struct_instances.is_empty() {
+            generate_blank_line(output_file);
+        }
+    }
+
     fn generate_rust_instances(&self, output_file: &mut impl std::io::Write) {
         for (operation, operation_instance_name) in self.operation_instances.iter() {
             let operation_type = pascal_case(&operation.operation_type);
@@ -153,8 +254,12 @@ impl private::Sealed for Model {
         }
     }
 
-    fn generate_rust_operation_list(&self, output_file: &mut impl std::io::Write) {
-        let array_name = macro_case(self.sequence_name.as_ref().unwrap());
+    fn generate_rust_operation_list(
+        &self,
+        sequence: &Sequence,
+        output_file: &mut impl std::io::Write,
+    ) {
+        let array_name = macro_case(&sequence.name);
 
         writeln!(
             output_file,
@@ -163,45 +268,112 @@ impl private::Sealed for Model {
         )
         .unwrap();
 
-        for op_ref in self.operation_ref_table.iter() {
+        let phase_markers = sequence.phase_markers();
+        let mut phase_markers = phase_markers.iter().peekable();
+        for (index, op_ref) in sequence.operation_ref_table.iter().enumerate() {
+            generate_phase_comments(&mut phase_markers, Some(index), "    ", output_file);
             let operation_id_member = pascal_case(&op_ref.operation_type);
             let rust_instance_name = macro_case(&op_ref.operation_variant_ref_name);
 
             writeln!(
                 output_file,
-                "    OperationVariant::{}(&{}),",
-                operation_id_member, rust_instance_name
+                "    OperationVariant::{}(&{}),{}",
+                operation_id_member,
+                rust_instance_name,
+                fmt_source_comment(self, op_ref)
             )
             .unwrap();
         }
+        generate_phase_comments(&mut phase_markers, None, "    ", output_file);
 
         write!(output_file, "];").unwrap();
     }
 
+    fn generate_rust_phase_ranges(
+        &self,
+        sequence: &Sequence,
+        output_file: &mut impl std::io::Write,
+    ) {
+        let phase_ranges = sequence.phase_ranges();
+        if phase_ranges.is_empty() {
+            return;
+        }
+
+        generate_blank_line(output_file);
+        for phase_range in phase_ranges.iter() {
+            let path: Vec<String> = phase_range
+                .path
+                .iter()
+                .map(|name| macro_case(name))
+                .collect();
+            generate_blank_line(output_file);
+            write!(
+                output_file,
+                "pub const {}_PHASE_{}: core::ops::Range<usize> = {}..{};",
+                macro_case(&sequence.name),
+                path.join("_"),
+                phase_range.start,
+                phase_range.end
+            )
+            .unwrap();
+        }
+    }
+
+    fn generate_rust_delay_list(&self, sequence: &Sequence, output_file: &mut impl std::io::Write) {
+        if !sequence.has_timing() {
+            return;
+        }
+        let array_name = macro_case(&sequence.name) + "_DELAYS_US";
+        let delays: Vec<u32> = sequence
+            .operation_ref_table
+            .iter()
+            .map(|op_ref| op_ref.delay_us)
+            .collect();
+
+        generate_blank_line(output_file);
+        generate_blank_line(output_file);
+        write!(
+            output_file,
+            "pub static {}: &[u32] = &{};",
+            array_name,
+            Self::fmt_rust_array_value(&delays, |e| format!("{}", e))
+        )
+        .unwrap();
+    }
+
     fn member_type_to_rust_type_string(member_type: &MemberType) -> String {
         match member_type {
             MemberType::X8 | MemberType::U8 => "u8".to_string(),
             MemberType::X16 | MemberType::U16 => "u16".to_string(),
             MemberType::X32 | MemberType::U32 => "u32".to_string(),
             MemberType::X64 | MemberType::U64 => "u64".to_string(),
+            MemberType::X128 | MemberType::U128 => "u128".to_string(),
             MemberType::I8 => "i8".to_string(),
             MemberType::I16 => "i16".to_string(),
             MemberType::I32 => "i32".to_string(),
             MemberType::I64 => "i64".to_string(),
+            MemberType::I128 => "i128".to_string(),
             MemberType::F32 => "f32".to_string(),
             MemberType::F64 => "f64".to_string(),
             MemberType::ArrayOfX8 | MemberType::ArrayOfU8 => "&'static [u8]".to_string(),
             MemberType::ArrayOfX16 | MemberType::ArrayOfU16 => "&'static [u16]".to_string(),
             MemberType::ArrayOfX32 | MemberType::ArrayOfU32 => "&'static [u32]".to_string(),
             MemberType::ArrayOfX64 | MemberType::ArrayOfU64 => "&'static [u64]".to_string(),
+            MemberType::ArrayOfX128 | MemberType::ArrayOfU128 => "&'static [u128]".to_string(),
             MemberType::ArrayOfI8 => "&'static [i8]".to_string(),
             MemberType::ArrayOfI16 => "&'static [i16]".to_string(),
             MemberType::ArrayOfI32 => "&'static [i32]".to_string(),
             MemberType::ArrayOfI64 => "&'static [i64]".to_string(),
+            MemberType::ArrayOfI128 => "&'static [i128]".to_string(),
             MemberType::ArrayOfF32 => "&'static [f32]".to_string(),
             MemberType::ArrayOfF64 => "&'static [f64]".to_string(),
             MemberType::Bool => "bool".to_string(),
             MemberType::Identifier(enum_type) => pascal_case(enum_type),
+            MemberType::Flags(flags_type) => pascal_case(flags_type),
+            MemberType::Struct(struct_type) => format!("&'static {}", pascal_case(struct_type)),
+            MemberType::ArrayOfStruct(struct_type) => {
+                format!("&'static [&'static {}]", pascal_case(struct_type))
+            }
         }
     }
 
@@ -255,6 +427,14 @@ impl private::Sealed for Model {
                 Self::fmt_rust_array_value(array, |e| format!("{}", e)),
                 "u64",
             ),
+            ArrayInstanceVariant::X128(array) => (
+                Self::fmt_rust_array_value(array, |e| format!("0x{:x}", e)),
+                "u128",
+            ),
+            ArrayInstanceVariant::U128(array) => (
+                Self::fmt_rust_array_value(array, |e| format!("{}", e)),
+                "u128",
+            ),
             ArrayInstanceVariant::I8(array) => (
                 Self::fmt_rust_array_value(array, |e| format!("{}", e)),
                 "i8",
@@ -271,6 +451,10 @@ impl private::Sealed for Model {
                 Self::fmt_rust_array_value(array, |e| format!("{}", e)),
                 "i64",
             ),
+            ArrayInstanceVariant::I128(array) => (
+                Self::fmt_rust_array_value(array, |e| format!("{}", e)),
+                "i128",
+            ),
             _ => {
                 unimplemented!(
                     "Array type in ArrayInstanceVariant not supported for Rust generation"
@@ -334,6 +518,18 @@ impl private::Sealed for Model {
                 let name = snake_case(&param.name);
                 format!("{}: {}", name, param.value)
             }
+            OperationParameterVariant::X128(param) => {
+                let name = snake_case(&param.name);
+                format!("{}: 0x{:x}", name, param.value)
+            }
+            OperationParameterVariant::U128(param) => {
+                let name = snake_case(&param.name);
+                format!("{}: {}", name, param.value)
+            }
+            OperationParameterVariant::I128(param) => {
+                let name = snake_case(&param.name);
+                format!("{}: {}", name, param.value)
+            }
             OperationParameterVariant::F32(param) => {
                 let name = snake_case(&param.name);
                 format!("{}: {}", name, param.value)
@@ -354,6 +550,9 @@ impl private::Sealed for Model {
             | OperationParameterVariant::ArrayOfX64(param)
             | OperationParameterVariant::ArrayOfU64(param)
             | OperationParameterVariant::ArrayOfI64(param)
+            | OperationParameterVariant::ArrayOfX128(param)
+            | OperationParameterVariant::ArrayOfU128(param)
+            | OperationParameterVariant::ArrayOfI128(param)
             | OperationParameterVariant::ArrayOfF32(param)
             | OperationParameterVariant::ArrayOfF64(param) => {
                 let name = snake_case(&param.name);
@@ -371,6 +570,27 @@ impl private::Sealed for Model {
                 let enum_value = pascal_case(&param.value);
                 format!("{}: {}::{}", name, enum_type, enum_value)
             }
+            OperationParameterVariant::Flags(param) => {
+                let name = snake_case(&param.name);
+                let flags_type = pascal_case(&param.flags_type);
+                let val_str = if param.flags.is_empty() {
+                    String::from("0")
+                } else {
+                    param
+                        .flags
+                        .iter()
+                        .map(|flag| format!("{}::{}.0", flags_type, macro_case(flag)))
+                        .collect::<Vec<String>>()
+                        .join(" | ")
+                };
+                format!("{}: {}({})", name, flags_type, val_str)
+            }
+            OperationParameterVariant::Struct(param) => {
+                format!("{}: &{}", snake_case(&param.name), macro_case(&param.value))
+            }
+            OperationParameterVariant::ArrayOfStruct(param) => {
+                format!("{}: {}", snake_case(&param.name), macro_case(&param.value))
+            }
         }
     }
 }
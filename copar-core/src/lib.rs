@@ -0,0 +1,27 @@
+//! # Copar core
+//! Parser, model and generators of copar, re-exported by the `copar` crate. They are kept apart
+//! so that `copar-macros` can parse the logs at compile time and be re-exported by `copar` too.
+pub mod build;
+mod check;
+mod compression;
+mod extract;
+mod generators;
+mod literal;
+mod model;
+mod parser;
+mod schema;
+mod stats;
+mod unirecord;
+
+pub use check::{CheckIssue, CheckIssueKind, CheckSeverity};
+pub use compression::CompressionError;
+pub use generators::c_emitter_generation::CEmitterGeneration;
+pub use generators::c_generation::CGeneration;
+pub use generators::c_sharp_generation::CSharpGeneration;
+pub use generators::rust_generation::RustGeneration;
+pub use literal::LiteralError;
+pub use model::{EnumDiscriminantError, Model, StructDefinitionError, UnknownSequenceError};
+pub use parser::{FileParsingError, LineParsingError, Parser, TimestampFormat};
+pub use schema::{Schema, SchemaError, SchemaViolation};
+pub use stats::{ModelStats, RecordStats};
+pub use unirecord::RecordParsingError;
@@ -0,0 +1,236 @@
+use std::fmt::Display;
+
+/// Error lexing an integer literal of a record argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiteralError {
+    Empty,
+    MissingDigits,
+    MissingHexPrefix,
+    InvalidDigit {
+        digit: char,
+        position: usize,
+        radix: u32,
+    },
+    MisplacedUnderscore {
+        position: usize,
+    },
+    FloatInIntegerContext,
+    NegativeUnsigned,
+    OutOfRange,
+}
+
+impl Display for LiteralError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty literal"),
+            Self::MissingDigits => write!(f, "no digits after the sign or the radix prefix"),
+            Self::MissingHexPrefix => write!(f, "hexadecimal literal must start with `0x`"),
+            Self::InvalidDigit {
+                digit,
+                position,
+                radix,
+            } => {
+                let radix_name = match radix {
+                    2 => "binary",
+                    8 => "octal",
+                    16 => "hexadecimal",
+                    _ => "decimal",
+                };
+                write!(
+                    f,
+                    "invalid {radix_name} digit `{digit}` at position {position}"
+                )
+            }
+            Self::MisplacedUnderscore { position } => write!(
+                f,
+                "underscore at position {position} does not separate two digits"
+            ),
+            Self::FloatInIntegerContext => {
+                write!(f, "floating-point literal given for an integer")
+            }
+            Self::NegativeUnsigned => write!(f, "negative literal given for an unsigned integer"),
+            Self::OutOfRange => write!(f, "literal out of the range of the type"),
+        }
+    }
+}
+
+impl std::error::Error for LiteralError {}
+
+/// Integer type built from the sign and magnitude of a literal
+pub(crate) trait LiteralInteger: Sized {
+    fn from_magnitude(negative: bool, magnitude: u128) -> Result<Self, LiteralError>;
+}
+
+macro_rules! impl_unsigned_literal_integer {
+    ($($integer_type:ty),*) => {
+        $(
+            impl LiteralInteger for $integer_type {
+                fn from_magnitude(negative: bool, magnitude: u128) -> Result<Self, LiteralError> {
+                    if negative && magnitude != 0 {
+                        return Err(LiteralError::NegativeUnsigned);
+                    }
+                    <$integer_type>::try_from(magnitude).map_err(|_| LiteralError::OutOfRange)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed_literal_integer {
+    ($($integer_type:ty),*) => {
+        $(
+            impl LiteralInteger for $integer_type {
+                fn from_magnitude(negative: bool, magnitude: u128) -> Result<Self, LiteralError> {
+                    let value = if negative {
+                        0i128.checked_sub_unsigned(magnitude)
+                    } else {
+                        i128::try_from(magnitude).ok()
+                    };
+                    value
+                        .and_then(|value| <$integer_type>::try_from(value).ok())
+                        .ok_or(LiteralError::OutOfRange)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_literal_integer!(u8, u16, u32, u64, u128);
+impl_signed_literal_integer!(i8, i16, i32, i64, i128);
+
+/// Parse an integer literal: an optional sign, an optional `0x`, `0o` or `0b` radix prefix and
+/// digits, optionally separated by underscores (eg. `-0x10`, `0b1010_0101`, `1_000_000`).
+/// Hexadecimal arguments (`x8` to `x128`) must have the `0x` prefix.
+pub(crate) fn parse_integer<T: LiteralInteger>(
+    literal: &str,
+    hexadecimal: bool,
+) -> Result<T, LiteralError> {
+    let literal = literal.trim();
+    if literal.is_empty() {
+        return Err(LiteralError::Empty);
+    }
+    let (negative, unsigned_literal) = match literal.strip_prefix('-') {
+        Some(unsigned_literal) => (true, unsigned_literal),
+        None => (false, literal.strip_prefix('+').unwrap_or(literal)),
+    };
+    let (radix, digits) = match unsigned_literal.get(..2) {
+        Some("0x" | "0X") => (16, &unsigned_literal[2..]),
+        Some("0o" | "0O") => (8, &unsigned_literal[2..]),
+        Some("0b" | "0B") => (2, &unsigned_literal[2..]),
+        _ => (10, unsigned_literal),
+    };
+    if hexadecimal && radix != 16 {
+        return Err(LiteralError::MissingHexPrefix);
+    }
+    if digits.is_empty() {
+        return Err(LiteralError::MissingDigits);
+    }
+    if radix == 10
+        && digits.contains(['.', 'e', 'E'])
+        && digits.replace('_', "").parse::<f64>().is_ok()
+    {
+        return Err(LiteralError::FloatInIntegerContext);
+    }
+
+    // Positions are counted in characters from the start of the literal, sign and prefix included
+    let digits_position = literal.len() - digits.len();
+    let mut magnitude: u128 = 0;
+    let mut out_of_range = false;
+    let mut after_digit = false;
+    for (index, character) in digits.chars().enumerate() {
+        let position = digits_position + index;
+        if character == '_' {
+            if !after_digit {
+                return Err(LiteralError::MisplacedUnderscore { position });
+            }
+            after_digit = false;
+            continue;
+        }
+        let digit = character
+            .to_digit(radix)
+            .ok_or(LiteralError::InvalidDigit {
+                digit: character,
+                position,
+                radix,
+            })?;
+        match magnitude
+            .checked_mul(radix.into())
+            .and_then(|magnitude| magnitude.checked_add(digit.into()))
+        {
+            Some(new_magnitude) => magnitude = new_magnitude,
+            None => out_of_range = true,
+        }
+        after_digit = true;
+    }
+    if !after_digit {
+        return Err(LiteralError::MisplacedUnderscore {
+            position: digits_position + digits.chars().count() - 1,
+        });
+    }
+    if out_of_range {
+        return Err(LiteralError::OutOfRange);
+    }
+    T::from_magnitude(negative, magnitude)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn integer_literal_lexing_test() {
+        assert_eq!(parse_integer::<u8>("0b1010", false), Ok(10));
+        assert_eq!(parse_integer::<u8>("0o17", false), Ok(15));
+        assert_eq!(parse_integer::<u32>("1_000_000", false), Ok(1_000_000));
+        assert_eq!(parse_integer::<i8>("-0x10", false), Ok(-16));
+        assert_eq!(parse_integer::<i8>("-128", false), Ok(i8::MIN));
+        assert_eq!(
+            parse_integer::<i128>("-0x8000_0000_0000_0000_0000_0000_0000_0000", false),
+            Ok(i128::MIN)
+        );
+        assert_eq!(parse_integer::<u16>("0xFF3A", true), Ok(0xFF3A));
+        assert_eq!(parse_integer::<u16>("+42", false), Ok(42));
+
+        assert_eq!(
+            parse_integer::<u8>("25", true),
+            Err(LiteralError::MissingHexPrefix)
+        );
+        assert_eq!(
+            parse_integer::<u32>("1e-3", false),
+            Err(LiteralError::FloatInIntegerContext)
+        );
+        assert_eq!(
+            parse_integer::<u8>("0b102", false),
+            Err(LiteralError::InvalidDigit {
+                digit: '2',
+                position: 4,
+                radix: 2
+            })
+        );
+        assert_eq!(
+            parse_integer::<u32>("1__0", false),
+            Err(LiteralError::MisplacedUnderscore { position: 2 })
+        );
+        assert_eq!(
+            parse_integer::<u32>("10_", false),
+            Err(LiteralError::MisplacedUnderscore { position: 2 })
+        );
+        assert_eq!(
+            parse_integer::<u8>("-1", false),
+            Err(LiteralError::NegativeUnsigned)
+        );
+        assert_eq!(
+            parse_integer::<u8>("256", false),
+            Err(LiteralError::OutOfRange)
+        );
+        assert_eq!(
+            parse_integer::<i8>("-129", false),
+            Err(LiteralError::OutOfRange)
+        );
+        assert_eq!(
+            parse_integer::<u8>("0x", true),
+            Err(LiteralError::MissingDigits)
+        );
+        assert_eq!(parse_integer::<u8>("", false), Err(LiteralError::Empty));
+    }
+}
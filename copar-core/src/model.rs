@@ -0,0 +1,1333 @@
+use crate::parser::CALL_RECORD_NAME;
+use crate::unirecord::{
+    FlagsRecordArg, IdentifierRecordArg, MemberType, UniRecord, UniRecordArgVariant,
+};
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::hash::Hash;
+
+use ordered_float::OrderedFloat;
+type OrderedF32 = OrderedFloat<f32>;
+type OrderedF64 = OrderedFloat<f64>;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct StructureDefinitionMember {
+    pub(crate) member_name: String,
+    pub(crate) member_type: MemberType,
+}
+
+/// Member of an enum, with its explicit discriminant if one was given
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EnumMember {
+    pub(crate) name: String,
+    pub(crate) discriminant: Option<u32>,
+}
+
+/// Enum discriminants conflicting with each other
+#[derive(Debug, PartialEq)]
+pub enum EnumDiscriminantError {
+    /// The member was already given another discriminant
+    Redefined {
+        enum_type: String,
+        member: String,
+        discriminant: u32,
+        previous_discriminant: u32,
+    },
+    /// Two members of the enum have the same value
+    Duplicate {
+        enum_type: String,
+        member: String,
+        other_member: String,
+        discriminant: u64,
+    },
+    /// The discriminant of a member, given or following the previous member, is above
+    /// `INT_MAX`, the largest value of a C enum constant
+    Overflow { enum_type: String, member: String },
+}
+
+impl Display for EnumDiscriminantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Redefined {
+                enum_type,
+                member,
+                discriminant,
+                previous_discriminant,
+            } => write!(
+                f,
+                "Enum value `{enum_type}::{member}` is given discriminant {discriminant:#x} but was given {previous_discriminant:#x}"
+            ),
+            Self::Duplicate {
+                enum_type,
+                member,
+                other_member,
+                discriminant,
+            } => write!(
+                f,
+                "Enum values `{enum_type}::{member}` and `{enum_type}::{other_member}` have the same discriminant {discriminant:#x}"
+            ),
+            Self::Overflow { enum_type, member } => write!(
+                f,
+                "Discriminant of enum value `{enum_type}::{member}` is above {:#x}, the largest C enum value",
+                i32::MAX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EnumDiscriminantError {}
+
+/// Structure value conflicting with the structure types and records already declared
+#[derive(Debug, PartialEq)]
+pub enum StructDefinitionError {
+    /// The fields differ from those of the previous values of the structure type
+    Redefined(String),
+    /// A record has the name of the structure type
+    RecordName(String),
+}
+
+impl Display for StructDefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Redefined(struct_type) => write!(
+                f,
+                "Structure `{struct_type}` has other fields than its previous values"
+            ),
+            Self::RecordName(name) => {
+                write!(f, "Structure type and record `{name}` have the same name")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StructDefinitionError {}
+
+/// Check that the members of the enum have distinct values. As in C, C# and Rust, a member
+/// without discriminant takes the value following the previous member, the first one being 0.
+pub(crate) fn check_enum_discriminants(
+    enum_type: &str,
+    members: &[EnumMember],
+) -> Result<(), EnumDiscriminantError> {
+    let mut member_names: HashMap<u64, &str> = HashMap::new();
+    let mut next_discriminant: u64 = 0;
+    for member in members.iter() {
+        let discriminant = member.discriminant.map_or(next_discriminant, u64::from);
+        if discriminant > i32::MAX as u64 {
+            return Err(EnumDiscriminantError::Overflow {
+                enum_type: enum_type.to_string(),
+                member: member.name.clone(),
+            });
+        }
+        if let Some(other_member) = member_names.insert(discriminant, &member.name) {
+            return Err(EnumDiscriminantError::Duplicate {
+                enum_type: enum_type.to_string(),
+                member: member.name.clone(),
+                other_member: other_member.to_string(),
+                discriminant,
+            });
+        }
+        next_discriminant = discriminant + 1;
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OperationParameter<T> {
+    pub(crate) name: String,
+    pub(crate) value: T,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct IdentifierOperationParameter {
+    pub(crate) name: String,
+    pub(crate) enum_type: String,
+    pub(crate) value: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct FlagsOperationParameter {
+    pub(crate) name: String,
+    pub(crate) flags_type: String,
+    /// Flags set in the value, in declaration order
+    pub(crate) flags: Vec<String>,
+}
+
+/// Maximum number of flags of a flags type, the values being 32 bits masks
+pub(crate) const MAX_FLAG_COUNT: usize = 32;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum OperationParameterVariant {
+    X8(OperationParameter<u8>),
+    X16(OperationParameter<u16>),
+    X32(OperationParameter<u32>),
+    X64(OperationParameter<u64>),
+    X128(OperationParameter<u128>),
+    U8(OperationParameter<u8>),
+    U16(OperationParameter<u16>),
+    U32(OperationParameter<u32>),
+    U64(OperationParameter<u64>),
+    U128(OperationParameter<u128>),
+    I8(OperationParameter<i8>),
+    I16(OperationParameter<i16>),
+    I32(OperationParameter<i32>),
+    I64(OperationParameter<i64>),
+    I128(OperationParameter<i128>),
+    F32(OperationParameter<OrderedF32>),
+    F64(OperationParameter<OrderedF64>),
+    ArrayOfX8(OperationParameter<String>),
+    ArrayOfX16(OperationParameter<String>),
+    ArrayOfX32(OperationParameter<String>),
+    ArrayOfX64(OperationParameter<String>),
+    ArrayOfX128(OperationParameter<String>),
+    ArrayOfU8(OperationParameter<String>),
+    ArrayOfU16(OperationParameter<String>),
+    ArrayOfU32(OperationParameter<String>),
+    ArrayOfU64(OperationParameter<String>),
+    ArrayOfU128(OperationParameter<String>),
+    ArrayOfI8(OperationParameter<String>),
+    ArrayOfI16(OperationParameter<String>),
+    ArrayOfI32(OperationParameter<String>),
+    ArrayOfI64(OperationParameter<String>),
+    ArrayOfI128(OperationParameter<String>),
+    ArrayOfF32(OperationParameter<String>),
+    ArrayOfF64(OperationParameter<String>),
+    Bool(OperationParameter<bool>),
+    Identifier(IdentifierOperationParameter),
+    Flags(FlagsOperationParameter),
+    /// Reference to a structure instance
+    Struct(OperationParameter<String>),
+    /// Reference to an array of structure instances
+    ArrayOfStruct(OperationParameter<String>),
+}
+
+impl OperationParameterVariant {
+    /// Name of the array instance referenced by the parameter, if it is an array
+    pub(crate) fn array_instance_name(&self) -> Option<&String> {
+        match self {
+            OperationParameterVariant::ArrayOfX8(parameter)
+            | OperationParameterVariant::ArrayOfX16(parameter)
+            | OperationParameterVariant::ArrayOfX32(parameter)
+            | OperationParameterVariant::ArrayOfX64(parameter)
+            | OperationParameterVariant::ArrayOfX128(parameter)
+            | OperationParameterVariant::ArrayOfU8(parameter)
+            | OperationParameterVariant::ArrayOfU16(parameter)
+            | OperationParameterVariant::ArrayOfU32(parameter)
+            | OperationParameterVariant::ArrayOfU64(parameter)
+            | OperationParameterVariant::ArrayOfU128(parameter)
+            | OperationParameterVariant::ArrayOfI8(parameter)
+            | OperationParameterVariant::ArrayOfI16(parameter)
+            | OperationParameterVariant::ArrayOfI32(parameter)
+            | OperationParameterVariant::ArrayOfI64(parameter)
+            | OperationParameterVariant::ArrayOfI128(parameter)
+            | OperationParameterVariant::ArrayOfF32(parameter)
+            | OperationParameterVariant::ArrayOfF64(parameter) => Some(&parameter.value),
+            _ => None,
+        }
+    }
+
+    /// Name of the structure instance or structure array referenced by the parameter
+    pub(crate) fn struct_instance_name(&self) -> Option<&String> {
+        match self {
+            OperationParameterVariant::Struct(parameter)
+            | OperationParameterVariant::ArrayOfStruct(parameter) => Some(&parameter.value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Operation {
+    pub(crate) operation_type: String,
+    pub(crate) parameters: Vec<OperationParameterVariant>,
+}
+
+/// Instance of a structure argument, or array of structure instances, referenced by the
+/// operations and the other structure instances
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) enum StructInstanceVariant {
+    Struct {
+        struct_type: String,
+        fields: Vec<OperationParameterVariant>,
+    },
+    Array {
+        struct_type: String,
+        instances: Vec<String>,
+    },
+}
+
+impl StructInstanceVariant {
+    /// Names of the structure instances and arrays referenced by the instance
+    fn referenced_instances(&self) -> Vec<&String> {
+        match self {
+            StructInstanceVariant::Struct { fields, .. } => fields
+                .iter()
+                .filter_map(OperationParameterVariant::struct_instance_name)
+                .collect(),
+            StructInstanceVariant::Array { instances, .. } => instances.iter().collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum ArrayInstanceVariant {
+    X8(Vec<u8>),
+    X16(Vec<u16>),
+    X32(Vec<u32>),
+    X64(Vec<u64>),
+    X128(Vec<u128>),
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+    U128(Vec<u128>),
+    I8(Vec<i8>),
+    I16(Vec<i16>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    I128(Vec<i128>),
+    F32(Vec<OrderedF32>),
+    F64(Vec<OrderedF64>),
+}
+
+/// Record of the log an operation was parsed from
+#[derive(Debug, Clone)]
+pub(crate) struct RecordSource {
+    /// Line of the log where the record starts
+    pub(crate) line_nb: usize,
+    /// Content of the record between its delimiters, the lines of a multiline or scope record
+    /// being joined
+    pub(crate) text: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct OperationTableMember {
+    pub(crate) operation_type: String,
+    pub(crate) operation_variant_ref_name: String,
+    /// Delay to wait before executing the operation, in microseconds
+    pub(crate) delay_us: u32,
+    /// `None` for the operations added by the compression
+    pub(crate) source: Option<RecordSource>,
+}
+
+/// Two table entries are equal when they execute the same operation after the same delay,
+/// wherever their records are in the log
+impl PartialEq for OperationTableMember {
+    fn eq(&self, other: &Self) -> bool {
+        self.operation_type == other.operation_type
+            && self.operation_variant_ref_name == other.operation_variant_ref_name
+            && self.delay_us == other.delay_us
+    }
+}
+
+/// Named section of a sequence, covering the operations of the table from `start` to `end`
+/// (excluded). Phases can be nested.
+#[derive(Debug)]
+pub struct Phase {
+    pub(crate) name: String,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) phases: Vec<Phase>,
+}
+
+/// Phase flattened with the names of its enclosing phases
+pub(crate) struct PhaseRange<'a> {
+    pub(crate) path: Vec<&'a str>,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl Phase {
+    fn collect_ranges<'a>(&'a self, parent_path: &[&'a str], ranges: &mut Vec<PhaseRange<'a>>) {
+        let mut path = parent_path.to_vec();
+        path.push(&self.name);
+        ranges.push(PhaseRange {
+            path: path.clone(),
+            start: self.start,
+            end: self.end,
+        });
+        for phase in self.phases.iter() {
+            phase.collect_ranges(&path, ranges);
+        }
+    }
+
+    /// Move the phase boundaries according to the new index of each table index
+    pub(crate) fn remap(&mut self, index_map: &[usize]) {
+        self.start = index_map[self.start];
+        self.end = index_map[self.end];
+        for phase in self.phases.iter_mut() {
+            phase.remap(index_map);
+        }
+    }
+
+    fn collect_markers(&self, parent_path: &str, markers: &mut Vec<(usize, String)>) {
+        let path = if parent_path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", parent_path, self.name)
+        };
+        markers.push((self.start, format!("phase {}", path)));
+        for phase in self.phases.iter() {
+            phase.collect_markers(&path, markers);
+        }
+        markers.push((self.end, format!("end of phase {}", path)));
+    }
+
+    fn collect_boundaries(&self, boundaries: &mut Vec<usize>) {
+        boundaries.push(self.start);
+        boundaries.push(self.end);
+        for phase in self.phases.iter() {
+            phase.collect_boundaries(boundaries);
+        }
+    }
+}
+
+/// Sequence of the log, delimited by the sequence begin and end records
+#[derive(Debug)]
+pub struct Sequence {
+    pub(crate) name: String,
+    pub(crate) operation_ref_table: Vec<OperationTableMember>,
+    pub(crate) phases: Vec<Phase>,
+    /// Key/value pairs of the begin record (eg. `device=rtl8822cs`), in the order of the log
+    pub(crate) metadata: Vec<(String, String)>,
+}
+
+impl Sequence {
+    /// Every phase of the sequence with its path, parents first
+    pub(crate) fn phase_ranges(&self) -> Vec<PhaseRange<'_>> {
+        let mut ranges = Vec::new();
+        for phase in self.phases.iter() {
+            phase.collect_ranges(&[], &mut ranges);
+        }
+        ranges
+    }
+
+    /// Comments marking the start and end of the phases, with the table index they are placed
+    /// before, in table order
+    pub(crate) fn phase_markers(&self) -> Vec<(usize, String)> {
+        let mut markers = Vec::new();
+        for phase in self.phases.iter() {
+            phase.collect_markers("", &mut markers);
+        }
+        markers
+    }
+
+    /// Sorted table indexes where a phase starts or ends
+    pub(crate) fn phase_boundaries(&self) -> Vec<usize> {
+        let mut boundaries = Vec::new();
+        for phase in self.phases.iter() {
+            phase.collect_boundaries(&mut boundaries);
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries
+    }
+
+    /// Whether any operation of the table has to be delayed
+    pub(crate) fn has_timing(&self) -> bool {
+        self.operation_ref_table
+            .iter()
+            .any(|operation_table_member| operation_table_member.delay_us != 0)
+    }
+}
+
+/// Sequence selected in a model but absent from the log
+#[derive(Debug)]
+pub struct UnknownSequenceError(pub String);
+
+impl std::fmt::Display for UnknownSequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sequence `{}` is not in the log", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSequenceError {}
+
+/// Copar model containing the full representation of a copar log file.
+/// Can be used to generate the commands table in multiple languages
+#[derive(Debug)]
+pub struct Model {
+    pub(crate) sequences: Vec<Sequence>,
+    pub(crate) array_instance_counter: usize,
+    pub(crate) operation_instance_counter: usize,
+    pub(crate) defined_enums: IndexMap<String, Vec<EnumMember>>,
+    /// Flags of each flags type, the mask of a flag being the bit of its index
+    pub(crate) defined_flags: IndexMap<String, Vec<String>>,
+    pub(crate) defined_records: IndexMap<String, Vec<StructureDefinitionMember>>,
+    pub(crate) instanciated_arrays: IndexMap<ArrayInstanceVariant, String>,
+    pub(crate) operation_instances: IndexMap<Operation, String>,
+    pub(crate) struct_instance_counter: usize,
+    /// Structure types of the structure arguments, a nested structure being defined before the
+    /// structures containing it
+    pub(crate) defined_structs: IndexMap<String, Vec<StructureDefinitionMember>>,
+    /// Structure instances and arrays, an instance being inserted after the instances it
+    /// references
+    pub(crate) struct_instances: IndexMap<StructInstanceVariant, String>,
+    /// Name of the log file, written in the header comment of the generated files when known
+    pub(crate) source_file_name: Option<String>,
+    /// FNV-1a hash of the content of the log, tracing the generated files back to their capture
+    pub(crate) source_hash: u64,
+    /// Whether the generators comment each table entry with the line of its record
+    pub(crate) source_annotations: bool,
+    /// Records generated by the parser or the compression rather than logged
+    pub(crate) generated_records: HashSet<String>,
+    /// Records of the expected-value records, named after the logged record with the
+    /// `Expect` suffix
+    pub(crate) expectation_records: HashSet<String>,
+}
+
+impl Model {
+    /// Start a new sequence, the following records are added to its operation table
+    pub(crate) fn begin_sequence(
+        &mut self,
+        sequence_name: String,
+        metadata: Vec<(String, String)>,
+    ) {
+        self.sequences.push(Sequence {
+            name: sequence_name,
+            operation_ref_table: Vec::new(),
+            phases: Vec::new(),
+            metadata,
+        });
+    }
+
+    /// Key/value metadata given on the begin record of the sequence, in the order of the log
+    pub fn sequence_metadata(&self, sequence_name: &str) -> Option<&[(String, String)]> {
+        self.sequences
+            .iter()
+            .find(|sequence| sequence.name == sequence_name)
+            .map(|sequence| sequence.metadata.as_slice())
+    }
+
+    /// Line and content of the record of the operation at `index` in the table of the sequence,
+    /// `None` for the operations added by the compression
+    pub fn operation_source(&self, sequence_name: &str, index: usize) -> Option<(usize, &str)> {
+        self.sequences
+            .iter()
+            .find(|sequence| sequence.name == sequence_name)
+            .and_then(|sequence| sequence.operation_ref_table.get(index))
+            .and_then(|operation| operation.source.as_ref())
+            .map(|source| (source.line_nb, source.text.as_str()))
+    }
+
+    /// Comment each entry of the generated operation tables with the log line of its record
+    /// (eg. `// init.log:1234 Cmd52`)
+    pub fn set_source_annotations(&mut self, source_annotations: bool) {
+        self.source_annotations = source_annotations;
+    }
+
+    /// Lines of the comment opening the generated files: the copar version, the log file and
+    /// its hash, and the metadata of the sequences
+    pub(crate) fn provenance_lines(&self) -> Vec<String> {
+        let source = match self.source_file_name.as_ref() {
+            Some(file_name) => format!(" from {file_name}"),
+            None => String::new(),
+        };
+        let mut lines = vec![
+            format!(
+                "Generated by copar {}{source}, do not edit.",
+                env!("CARGO_PKG_VERSION")
+            ),
+            format!("Log content hash (FNV-1a 64): {:#018x}", self.source_hash),
+        ];
+        for sequence in self.sequences.iter() {
+            if sequence.metadata.is_empty() {
+                continue;
+            }
+            let metadata: Vec<String> = sequence
+                .metadata
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect();
+            lines.push(format!(
+                "Sequence {}: {}",
+                sequence.name,
+                metadata.join(", ")
+            ));
+        }
+        lines
+    }
+
+    /// Sequence the records are currently added to
+    pub(crate) fn current_sequence(&mut self) -> &mut Sequence {
+        self.sequences
+            .last_mut()
+            .expect("Records are only added inside a sequence")
+    }
+
+    /// Name of the first sequence of the log, naming the generated files
+    pub(crate) fn main_sequence_name(&self) -> Option<&String> {
+        self.sequences.first().map(|sequence| &sequence.name)
+    }
+
+    /// Names of the sequences of the log, in order of appearance
+    pub fn sequence_names(&self) -> impl Iterator<Item = &String> {
+        self.sequences.iter().map(|sequence| &sequence.name)
+    }
+
+    /// Names of the sequences called by the sequence
+    fn called_sequences<'a>(&'a self, sequence: &'a Sequence) -> impl Iterator<Item = &'a String> {
+        sequence
+            .operation_ref_table
+            .iter()
+            .filter(|operation_table_member| {
+                operation_table_member.operation_type == CALL_RECORD_NAME
+            })
+            .filter_map(|operation_table_member| {
+                self.operation_instances.iter().find(|(_, instance_name)| {
+                    **instance_name == operation_table_member.operation_variant_ref_name
+                })
+            })
+            .flat_map(|(operation, _)| operation.parameters.iter())
+            .filter_map(|parameter| match parameter {
+                OperationParameterVariant::Identifier(parameter) => Some(&parameter.value),
+                _ => None,
+            })
+    }
+
+    /// Keep only the given sequences and the sequences they call, in the order of the log, and
+    /// drop the instances and the type definitions no longer referenced by any of them.
+    pub fn select_sequences(
+        &mut self,
+        sequence_names: &[impl AsRef<str>],
+    ) -> Result<(), UnknownSequenceError> {
+        for sequence_name in sequence_names.iter() {
+            if !self
+                .sequence_names()
+                .any(|name| name == sequence_name.as_ref())
+            {
+                return Err(UnknownSequenceError(sequence_name.as_ref().to_string()));
+            }
+        }
+        let mut selected_sequences: HashSet<String> = sequence_names
+            .iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        let mut unvisited_sequences: Vec<String> = selected_sequences.iter().cloned().collect();
+        while let Some(sequence_name) = unvisited_sequences.pop() {
+            let Some(sequence) = self
+                .sequences
+                .iter()
+                .find(|sequence| sequence.name == sequence_name)
+            else {
+                continue;
+            };
+            for called_sequence in self.called_sequences(sequence) {
+                if selected_sequences.insert(called_sequence.clone()) {
+                    unvisited_sequences.push(called_sequence.clone());
+                }
+            }
+        }
+        self.sequences
+            .retain(|sequence| selected_sequences.contains(&sequence.name));
+
+        let referenced_instances: HashSet<String> = self
+            .sequences
+            .iter()
+            .flat_map(|sequence| sequence.operation_ref_table.iter())
+            .map(|operation_table_member| operation_table_member.operation_variant_ref_name.clone())
+            .collect();
+        self.operation_instances
+            .retain(|_, instance_name| referenced_instances.contains(instance_name));
+
+        // Instances only reference earlier instances, walking them backwards reaches them all
+        let mut referenced_structs: HashSet<String> = self
+            .operation_instances
+            .keys()
+            .flat_map(|operation| operation.parameters.iter())
+            .filter_map(OperationParameterVariant::struct_instance_name)
+            .cloned()
+            .collect();
+        for (struct_instance, instance_name) in self.struct_instances.iter().rev() {
+            if referenced_structs.contains(instance_name) {
+                referenced_structs
+                    .extend(struct_instance.referenced_instances().into_iter().cloned());
+            }
+        }
+        self.struct_instances
+            .retain(|_, instance_name| referenced_structs.contains(instance_name));
+
+        let struct_fields =
+            self.struct_instances
+                .keys()
+                .flat_map(|struct_instance| match struct_instance {
+                    StructInstanceVariant::Struct { fields, .. } => fields.iter(),
+                    StructInstanceVariant::Array { .. } => [].iter(),
+                });
+        let referenced_arrays: HashSet<&String> = self
+            .operation_instances
+            .keys()
+            .flat_map(|operation| operation.parameters.iter())
+            .chain(struct_fields)
+            .filter_map(OperationParameterVariant::array_instance_name)
+            .collect();
+        self.instanciated_arrays
+            .retain(|_, array_name| referenced_arrays.contains(array_name));
+
+        let referenced_records: HashSet<&String> = self
+            .operation_instances
+            .keys()
+            .map(|operation| &operation.operation_type)
+            .collect();
+        self.defined_records
+            .retain(|record_name, _| referenced_records.contains(record_name));
+        let referenced_structs: HashSet<&String> = self
+            .struct_instances
+            .keys()
+            .map(|struct_instance| match struct_instance {
+                StructInstanceVariant::Struct { struct_type, .. }
+                | StructInstanceVariant::Array { struct_type, .. } => struct_type,
+            })
+            .collect();
+        self.defined_structs
+            .retain(|struct_type, _| referenced_structs.contains(struct_type));
+        let struct_fields =
+            self.struct_instances
+                .keys()
+                .flat_map(|struct_instance| match struct_instance {
+                    StructInstanceVariant::Struct { fields, .. } => fields.iter(),
+                    StructInstanceVariant::Array { .. } => [].iter(),
+                });
+        let parameters: Vec<&OperationParameterVariant> = self
+            .operation_instances
+            .keys()
+            .flat_map(|operation| operation.parameters.iter())
+            .chain(struct_fields)
+            .collect();
+        let referenced_enums: HashSet<&String> = parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                OperationParameterVariant::Identifier(parameter) => Some(&parameter.enum_type),
+                _ => None,
+            })
+            .collect();
+        self.defined_enums
+            .retain(|enum_type, _| referenced_enums.contains(enum_type));
+        let referenced_flags: HashSet<&String> = parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                OperationParameterVariant::Flags(parameter) => Some(&parameter.flags_type),
+                _ => None,
+            })
+            .collect();
+        self.defined_flags
+            .retain(|flags_type, _| referenced_flags.contains(flags_type));
+        Ok(())
+    }
+
+    fn add_identifier_declaration(&mut self, argument: &IdentifierRecordArg) {
+        let enum_type = argument.enum_type.clone();
+        let enum_member = EnumMember {
+            name: argument.value.clone(),
+            discriminant: None,
+        };
+
+        // If no declaration exists for this enum, create it
+        if let Some(enum_decl) = self.defined_enums.get_mut(&enum_type) {
+            if !enum_decl
+                .iter()
+                .any(|member| member.name == enum_member.name)
+            {
+                enum_decl.push(enum_member);
+            }
+        } else {
+            let new_enum_decl = vec![enum_member];
+            self.defined_enums.insert(enum_type, new_enum_decl);
+        }
+    }
+
+    /// Declare the enum member of the argument with its discriminant, failing if the
+    /// discriminant conflicts with the ones of the enum
+    pub(crate) fn declare_enum_member(
+        &mut self,
+        argument: &IdentifierRecordArg,
+    ) -> Result<(), EnumDiscriminantError> {
+        let mut enum_decl = self
+            .defined_enums
+            .get(&argument.enum_type)
+            .cloned()
+            .unwrap_or_default();
+        match enum_decl
+            .iter_mut()
+            .find(|member| member.name == argument.value)
+        {
+            Some(member) => match (member.discriminant, argument.discriminant) {
+                (Some(previous_discriminant), Some(discriminant))
+                    if previous_discriminant != discriminant =>
+                {
+                    return Err(EnumDiscriminantError::Redefined {
+                        enum_type: argument.enum_type.clone(),
+                        member: argument.value.clone(),
+                        discriminant,
+                        previous_discriminant,
+                    });
+                }
+                (None, Some(discriminant)) => member.discriminant = Some(discriminant),
+                _ => return Ok(()),
+            },
+            None => enum_decl.push(EnumMember {
+                name: argument.value.clone(),
+                discriminant: argument.discriminant,
+            }),
+        }
+        check_enum_discriminants(&argument.enum_type, &enum_decl)?;
+        self.defined_enums
+            .insert(argument.enum_type.clone(), enum_decl);
+        Ok(())
+    }
+
+    /// Declare the flags of the argument in order of first appearance. Returns false, leaving
+    /// the flags type unchanged, if it would have more than [`MAX_FLAG_COUNT`] flags.
+    pub(crate) fn declare_flags(&mut self, argument: &FlagsRecordArg) -> bool {
+        let flags_decl = self
+            .defined_flags
+            .entry(argument.flags_type.clone())
+            .or_default();
+        let new_flags: Vec<&String> = argument
+            .flags
+            .iter()
+            .filter(|flag| !flags_decl.contains(flag))
+            .collect();
+        if flags_decl.len() + new_flags.len() > MAX_FLAG_COUNT {
+            return false;
+        }
+        flags_decl.extend(new_flags.into_iter().cloned());
+        true
+    }
+
+    /// Declare the structure type of a structure value, once the structures nested in its fields
+    /// are declared. Fails if the fields differ from the previous values of the type or if a
+    /// record has its name.
+    pub(crate) fn declare_struct(
+        &mut self,
+        struct_type: &str,
+        fields: &[UniRecordArgVariant],
+    ) -> Result<(), StructDefinitionError> {
+        if self.defined_records.contains_key(struct_type) {
+            return Err(StructDefinitionError::RecordName(struct_type.to_string()));
+        }
+        let definition = structure_definition(fields);
+        match self.defined_structs.get(struct_type) {
+            Some(previous_definition) if *previous_definition != definition => {
+                Err(StructDefinitionError::Redefined(struct_type.to_string()))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.defined_structs
+                    .insert(struct_type.to_string(), definition);
+                Ok(())
+            }
+        }
+    }
+
+    /// Mask of a declared flag
+    pub(crate) fn flag_mask(&self, flags_type: &str, flag: &str) -> u32 {
+        let index = self.defined_flags[flags_type]
+            .iter()
+            .position(|declared_flag| declared_flag == flag)
+            .unwrap();
+        1 << index
+    }
+
+    fn add_array_instance(&mut self, array_variant: ArrayInstanceVariant) -> String {
+        if let Some(array_instance_name) = self.instanciated_arrays.get_mut(&array_variant) {
+            array_instance_name.clone()
+        } else {
+            let new_array_instance_name =
+                String::from("array_") + self.array_instance_counter.to_string().as_str();
+            self.array_instance_counter += 1;
+            self.instanciated_arrays
+                .insert(array_variant, new_array_instance_name.clone());
+            new_array_instance_name
+        }
+    }
+
+    pub(crate) fn add_structure_definition(
+        &mut self,
+        record_name: String,
+        record_args: &[UniRecordArgVariant],
+    ) {
+        // If no declaration exists for this record, create it
+        if self.defined_records.get(&record_name).is_none() {
+            self.defined_records
+                .insert(record_name, structure_definition(record_args));
+        }
+    }
+
+    /// Register the structure instance, its nested structures and arrays, returning its name
+    fn add_struct_instance(
+        &mut self,
+        struct_type: String,
+        fields: Vec<UniRecordArgVariant>,
+    ) -> String {
+        let definition = structure_definition(&fields);
+        let fields = fields
+            .into_iter()
+            .map(|field| self.add_operation_parameter(field))
+            .collect();
+        // Inserted after the nested structures, which are defined first in the generated code
+        self.defined_structs
+            .entry(struct_type.clone())
+            .or_insert(definition);
+        self.insert_struct_instance(StructInstanceVariant::Struct {
+            struct_type,
+            fields,
+        })
+    }
+
+    fn insert_struct_instance(&mut self, struct_instance: StructInstanceVariant) -> String {
+        if let Some(instance_name) = self.struct_instances.get(&struct_instance) {
+            return instance_name.clone();
+        }
+        let prefix = match struct_instance {
+            StructInstanceVariant::Struct { .. } => "struct_",
+            StructInstanceVariant::Array { .. } => "struct_array_",
+        };
+        let instance_name = format!("{prefix}{}", self.struct_instance_counter);
+        self.struct_instance_counter += 1;
+        self.struct_instances
+            .insert(struct_instance, instance_name.clone());
+        instance_name
+    }
+
+    /// Turn a record argument into an operation parameter, registering the arrays and structure
+    /// instances it references
+    fn add_operation_parameter(&mut self, arg: UniRecordArgVariant) -> OperationParameterVariant {
+        match arg {
+            UniRecordArgVariant::X8(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::X8(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::X16(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::X16(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::X32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::X32(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::X64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::X64(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::X128(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::X128(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::U8(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::U8(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::U16(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::U16(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::U32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::U32(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::U64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::U64(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::U128(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::U128(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::I8(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::I8(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::I16(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::I16(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::I32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::I32(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::I64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::I64(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::I128(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::I128(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::F32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::F32(OperationParameter {
+                    name: arg_name,
+                    value: OrderedF32::from(arg_value),
+                })
+            }
+            UniRecordArgVariant::F64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::F64(OperationParameter {
+                    name: arg_name,
+                    value: OrderedF64::from(arg_value),
+                })
+            }
+            UniRecordArgVariant::ArrayOfX8(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::X8(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfX8(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfX16(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::X16(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfX16(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfX32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::X32(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfX32(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfX64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::X64(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfX64(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfX128(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::X128(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfX128(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfU8(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::U8(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfU8(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfU16(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::U16(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfU16(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfU32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::U32(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfU32(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfU64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::U64(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfU64(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfU128(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::U128(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfU128(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfI8(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::I8(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfI8(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfI16(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::I16(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfI16(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfI32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::I32(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfI32(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfI64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::I64(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfI64(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::ArrayOfI128(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let array_instance = ArrayInstanceVariant::I128(arg_value);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfI128(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfF32(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let f32_array: Vec<OrderedF32> =
+                    arg_value.iter().map(|x| OrderedF32::from(*x)).collect();
+                let array_instance = ArrayInstanceVariant::F32(f32_array);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfF32(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+
+            UniRecordArgVariant::ArrayOfF64(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+                let f64_array: Vec<OrderedF64> =
+                    arg_value.iter().map(|x| OrderedF64::from(*x)).collect();
+                let array_instance = ArrayInstanceVariant::F64(f64_array);
+                let parameter_value = self.add_array_instance(array_instance);
+                OperationParameterVariant::ArrayOfF64(OperationParameter {
+                    name: arg_name,
+                    value: parameter_value,
+                })
+            }
+            UniRecordArgVariant::Bool(arg) => {
+                let (arg_name, arg_value) = arg.dissassemble();
+
+                OperationParameterVariant::Bool(OperationParameter {
+                    name: arg_name,
+                    value: arg_value,
+                })
+            }
+            UniRecordArgVariant::Flags(arg) => {
+                self.declare_flags(&arg);
+                let (flags_name, flags_type, mut flags) = arg.dissassemble();
+                // The same value written in another order is the same instance
+                flags.sort_by_key(|flag| self.flag_mask(&flags_type, flag));
+
+                OperationParameterVariant::Flags(FlagsOperationParameter {
+                    name: flags_name,
+                    flags_type,
+                    flags,
+                })
+            }
+            UniRecordArgVariant::Struct(arg) => {
+                let (arg_name, struct_type, fields) = arg.dissassemble();
+
+                OperationParameterVariant::Struct(OperationParameter {
+                    name: arg_name,
+                    value: self.add_struct_instance(struct_type, fields),
+                })
+            }
+            UniRecordArgVariant::ArrayOfStruct(arg) => {
+                let (arg_name, struct_type, elements) = arg.dissassemble();
+                let instances = elements
+                    .into_iter()
+                    .map(|fields| self.add_struct_instance(struct_type.clone(), fields))
+                    .collect();
+
+                OperationParameterVariant::ArrayOfStruct(OperationParameter {
+                    name: arg_name,
+                    value: self.insert_struct_instance(StructInstanceVariant::Array {
+                        struct_type,
+                        instances,
+                    }),
+                })
+            }
+            UniRecordArgVariant::Identifier(arg) => {
+                self.add_identifier_declaration(&arg);
+                let (enum_name, enum_type, enum_value) = arg.dissassemble();
+
+                OperationParameterVariant::Identifier(IdentifierOperationParameter {
+                    name: enum_name,
+                    enum_type,
+                    value: enum_value,
+                })
+            }
+        }
+    }
+
+    /// Add the operation described by the record to the table of the current sequence
+    pub(crate) fn add_record(&mut self, record: UniRecord, delay_us: u32, source: RecordSource) {
+        let mut operation_table_member = self.add_operation(record, delay_us);
+        operation_table_member.source = Some(source);
+        self.current_sequence()
+            .operation_ref_table
+            .push(operation_table_member);
+    }
+
+    /// Register the operation described by the record and return the table member referencing
+    /// its instance, without adding it to the operation table.
+    pub(crate) fn add_operation(
+        &mut self,
+        record: UniRecord,
+        delay_us: u32,
+    ) -> OperationTableMember {
+        let (record_type, record_args) = record.dissassemble();
+        self.add_structure_definition(record_type.clone(), &record_args);
+
+        let operation_parameters = record_args
+            .into_iter()
+            .map(|arg| self.add_operation_parameter(arg))
+            .collect();
+
+        let operation = Operation {
+            operation_type: record_type.clone(),
+            parameters: operation_parameters,
+        };
+
+        let operation_instance_name;
+        if let Some(operation_instance_name_) = self.operation_instances.get_mut(&operation) {
+            operation_instance_name = operation_instance_name_.clone();
+        } else {
+            operation_instance_name =
+                String::from("operation_") + self.operation_instance_counter.to_string().as_str();
+            self.operation_instance_counter += 1;
+            self.operation_instances
+                .insert(operation, operation_instance_name.clone());
+        }
+
+        OperationTableMember {
+            operation_type: record_type,
+            operation_variant_ref_name: operation_instance_name,
+            delay_us,
+            source: None,
+        }
+    }
+}
+
+/// Members of the structure holding the arguments
+fn structure_definition(args: &[UniRecordArgVariant]) -> Vec<StructureDefinitionMember> {
+    args.iter()
+        .map(|arg| StructureDefinitionMember {
+            member_name: arg.get_name().clone(),
+            member_type: arg.get_type(),
+        })
+        .collect()
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Model {
+            sequences: Vec::new(),
+            array_instance_counter: 0,
+            operation_instance_counter: 0,
+            defined_enums: IndexMap::new(),
+            defined_flags: IndexMap::new(),
+            defined_records: IndexMap::new(),
+            instanciated_arrays: IndexMap::new(),
+            operation_instances: IndexMap::new(),
+            struct_instance_counter: 0,
+            defined_structs: IndexMap::new(),
+            struct_instances: IndexMap::new(),
+            source_file_name: None,
+            source_hash: 0,
+            source_annotations: false,
+            generated_records: HashSet::new(),
+            expectation_records: HashSet::new(),
+        }
+    }
+}
@@ -0,0 +1,977 @@
+use crate::check::RecordLinter;
+use crate::model::{self, EnumDiscriminantError, StructDefinitionError};
+use crate::schema::{Schema, SchemaViolation};
+use crate::{model::Model, unirecord::RecordParsingError};
+use std::{char, fmt::Display, io};
+
+use crate::unirecord::{split_record_args, UniRecord, UniRecordArgVariant};
+enum RecordCapturingState {
+    OneShot,
+    Multiline(char),
+    Ranged,
+}
+
+pub type ParserResult<T> = Result<T, LineParsingError>;
+
+#[derive(Debug)]
+pub enum LineParsingError {
+    MultipleRecordDelimiters(String),
+    UnmatchedNameSequenceStop(Box<(String, String)>),
+    BadRecordArg(RecordParsingError),
+    UnmatchedRangedRecordName(Box<(String, String)>),
+    UncompleteRecordArg,
+    UnmatchingRecordDelimiters(char, char),
+    MissingEndDelimiter(char),
+    MissingBeginDelimiter(char),
+    UnparsableLine,
+    MissingRecordArgs,
+    BadDelayRecord,
+    TrailingDelay,
+    BadCallRecord,
+    ReservedCallRecord,
+    ReservedExpectRecord(String),
+    DuplicateSequence(String),
+    BadPhaseRecord,
+    UnmatchedPhaseEnd(Box<(String, Option<String>)>),
+    DuplicatePhase(String),
+    UnterminatedPhase(String),
+    UnterminatedScope(String),
+    SchemaViolation(Box<SchemaViolation>),
+    EnumDiscriminant(Box<EnumDiscriminantError>),
+    StructDefinition(StructDefinitionError),
+    TooManyFlags(String),
+    BadSequenceMetadata(String),
+}
+
+impl Display for LineParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MultipleRecordDelimiters(line) => {
+                write!(f, "Several record delimiters found in `{line}`")
+            }
+            Self::UnmatchedNameSequenceStop(names) => write!(
+                f,
+                "Sequence end `{}` does not match the sequence `{}`",
+                names.0, names.1
+            ),
+            Self::BadRecordArg(e) => write!(f, "Bad record argument: {e}"),
+            Self::UnmatchedRangedRecordName(names) => write!(
+                f,
+                "Scope record `{}` does not match the open scope `{}`",
+                names.1, names.0
+            ),
+            Self::UncompleteRecordArg => write!(f, "Record argument has a name but no value"),
+            Self::UnmatchingRecordDelimiters(begin, end) => write!(
+                f,
+                "Record begin delimiter `#{begin}` does not match end delimiter `{end}#`"
+            ),
+            Self::MissingEndDelimiter(delimiter) => {
+                write!(f, "Record has no end delimiter `{delimiter}#`")
+            }
+            Self::MissingBeginDelimiter(delimiter) => {
+                write!(f, "Record has no begin delimiter `#{delimiter}`")
+            }
+            Self::UnparsableLine => write!(f, "Line cannot be parsed"),
+            Self::MissingRecordArgs => write!(f, "Record has no arguments"),
+            Self::BadDelayRecord => write!(
+                f,
+                "Delay record must be `delay` followed by `us` or `ms` integer arguments"
+            ),
+            Self::TrailingDelay => write!(f, "Delay record is not followed by any record"),
+            Self::BadCallRecord => write!(
+                f,
+                "Call record must have a single `id(...)` argument naming the called sequence"
+            ),
+            Self::ReservedCallRecord => write!(
+                f,
+                "Record name `{CALL_RECORD_NAME}` is reserved for the sequence calls of the log"
+            ),
+            Self::ReservedExpectRecord(name) => write!(
+                f,
+                "Record name `{name}` is both logged and generated by expected-value records"
+            ),
+            Self::DuplicateSequence(name) => write!(f, "Sequence `{name}` is defined twice"),
+            Self::BadPhaseRecord => write!(f, "Phase record must be `phase` followed by a name"),
+            Self::UnmatchedPhaseEnd(names) => match &names.1 {
+                Some(open_phase) => write!(
+                    f,
+                    "Phase end `{}` does not match the open phase `{}`",
+                    names.0, open_phase
+                ),
+                None => write!(f, "Phase end `{}` without any open phase", names.0),
+            },
+            Self::DuplicatePhase(name) => write!(f, "Phase `{name}` is defined twice"),
+            Self::UnterminatedPhase(name) => {
+                write!(f, "Phase `{name}` is not ended before the sequence end")
+            }
+            Self::UnterminatedScope(name) => write!(f, "Scope record `{name}` is not ended"),
+            Self::SchemaViolation(e) => write!(f, "{e}"),
+            Self::EnumDiscriminant(e) => write!(f, "{e}"),
+            Self::StructDefinition(e) => write!(f, "{e}"),
+            Self::TooManyFlags(flags_type) => write!(
+                f,
+                "Flags type `{flags_type}` has more than {} flags",
+                model::MAX_FLAG_COUNT
+            ),
+            Self::BadSequenceMetadata(metadata) => write!(
+                f,
+                "Sequence metadata `{metadata}` must be a `key=value` pair given once"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LineParsingError {}
+
+type FileParsingResult<T> = Result<T, FileParsingError>;
+#[derive(Debug)]
+pub enum FileParsingError {
+    LineError {
+        line_nb: usize,
+        line_error: LineParsingError,
+    },
+    NoSequenceStart,
+    NoSequenceEnd {
+        last_record_start_line: usize,
+    },
+    UnknownSequenceCall {
+        line_nb: usize,
+        sequence_name: String,
+    },
+    RecursiveSequenceCall {
+        cycle: Vec<String>,
+    },
+}
+
+impl Display for FileParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LineError {
+                line_nb,
+                line_error,
+            } => write!(f, "line {line_nb}: {line_error}"),
+            Self::NoSequenceStart => write!(f, "No sequence begin record `#< name <#` found"),
+            Self::NoSequenceEnd {
+                last_record_start_line,
+            } => write!(
+                f,
+                "Sequence has no end record, last record started at line {last_record_start_line}"
+            ),
+            Self::UnknownSequenceCall {
+                line_nb,
+                sequence_name,
+            } => write!(
+                f,
+                "line {line_nb}: Call of unknown sequence `{sequence_name}`"
+            ),
+            Self::RecursiveSequenceCall { cycle } => {
+                write!(f, "Recursive sequence calls: {}", cycle.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for FileParsingError {}
+
+/// Call of a sequence from another one
+struct SequenceCall {
+    caller: String,
+    callee: String,
+    line_nb: usize,
+}
+
+/// Format of the timestamps prefixing the lines of the log, used to capture the delays
+/// between operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Kernel log timestamp in seconds with a fractional part (eg. `[   12.345678] `)
+    KernelLog,
+}
+
+/// Name of the delay record (eg. `#~ delay us: u32(500) ~#`)
+const DELAY_RECORD_NAME: &str = "delay";
+/// Name of the record calling another sequence (eg. `#= @call seq: id(Seq::power_on) =#`)
+const CALL_RECORD_TAG: &str = "@call";
+/// Name of the operation generated for a sequence call
+pub const CALL_RECORD_NAME: &str = "Call";
+/// Tag of the records delimiting a phase of a sequence (eg. `#< phase clock_setup <#`)
+const PHASE_RECORD_TAG: &str = "phase";
+
+/// Copar command parser. Initialized with the input file.
+pub struct Parser {
+    sequence_name: String,
+    pub(crate) input_file_buffer: Option<String>,
+    model: Model,
+    capturing_state: RecordCapturingState,
+    last_record_start_line: usize,
+    current_line: usize,
+    line_buffer: String,
+    pub(crate) timestamp_format: Option<TimestampFormat>,
+    current_timestamp_us: Option<u64>,
+    record_start_timestamp_us: Option<u64>,
+    last_operation_timestamp_us: Option<u64>,
+    pending_delay_us: u64,
+    sequence_calls: Vec<SequenceCall>,
+    open_phases: Vec<model::Phase>,
+    /// Collects the issues of the records, only when checking the log
+    pub(crate) linter: Option<RecordLinter>,
+    schema: Option<Schema>,
+}
+
+impl Parser {
+    /// Shorthand that create a temporary parser to immediately parse a file and output the model.
+    pub fn parse(input_file: impl io::Read) -> Result<Model, FileParsingError> {
+        let parser = Parser::new(input_file);
+        parser.parse_file()
+    }
+
+    /// Instanciate this structure with the log file containing the copar records
+    pub fn new(mut input_file: impl std::io::Read) -> Parser {
+        let mut input_file_buffer = String::new();
+        input_file.read_to_string(&mut input_file_buffer).unwrap();
+        let model = Model {
+            source_hash: fnv1a_hash(input_file_buffer.as_bytes()),
+            ..Model::default()
+        };
+
+        Parser {
+            sequence_name: String::new(),
+            input_file_buffer: Some(input_file_buffer),
+            model,
+            capturing_state: RecordCapturingState::OneShot,
+            last_record_start_line: 0,
+            current_line: 0,
+            line_buffer: String::new(),
+            timestamp_format: None,
+            current_timestamp_us: None,
+            record_start_timestamp_us: None,
+            last_operation_timestamp_us: None,
+            pending_delay_us: 0,
+            sequence_calls: Vec::new(),
+            open_phases: Vec::new(),
+            linter: None,
+            schema: None,
+        }
+    }
+
+    /// Capture the delays between operations from the timestamps prefixing the log lines
+    pub fn with_timestamps(mut self, timestamp_format: TimestampFormat) -> Parser {
+        self.timestamp_format = Some(timestamp_format);
+        self
+    }
+
+    /// Name the log file in the header comment of the generated files
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Parser {
+        self.model.source_file_name = Some(file_name.into());
+        self
+    }
+
+    /// Validate every record against the schema, the generators emitting the records and enums
+    /// of the schema in its order
+    pub fn with_schema(mut self, schema: Schema) -> Parser {
+        self.model.declare_schema(&schema);
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Split the timestamp from the line content. Lines without timestamp are left untouched.
+    pub(crate) fn strip_timestamp(
+        timestamp_format: Option<TimestampFormat>,
+        line: &str,
+    ) -> (Option<u64>, &str) {
+        match timestamp_format {
+            Some(TimestampFormat::KernelLog) => {
+                let Some((timestamp, content)) =
+                    line.strip_prefix('[').and_then(|line| line.split_once(']'))
+                else {
+                    return (None, line);
+                };
+                let (seconds, fraction) =
+                    timestamp.trim().split_once('.').unwrap_or((timestamp, ""));
+                let Ok(seconds) = seconds.trim().parse::<u64>() else {
+                    return (None, line);
+                };
+                let fraction: String = fraction.chars().chain("000000".chars()).take(6).collect();
+                let Ok(micros) = fraction.parse::<u64>() else {
+                    return (None, line);
+                };
+                (Some(seconds * 1_000_000 + micros), content.trim_start())
+            }
+            None => (None, line),
+        }
+    }
+
+    fn start_record(&mut self) {
+        self.last_record_start_line = self.current_line;
+        self.record_start_timestamp_us = self.current_timestamp_us;
+    }
+
+    fn add_record(&mut self, uni_record: UniRecord) -> ParserResult<()> {
+        // Calls are generated by the parser from `@call` records
+        let is_generated = self.model.generated_records.contains(uni_record.get_name());
+        let uni_record = match self.schema.as_ref().filter(|_| !is_generated) {
+            Some(schema) => schema
+                .validate(uni_record)
+                .map_err(|e| LineParsingError::SchemaViolation(Box::new(e)))?,
+            None => uni_record,
+        };
+        self.declare_arg_types(uni_record.get_args())?;
+        if self
+            .model
+            .defined_structs
+            .contains_key(uni_record.get_name())
+        {
+            return Err(LineParsingError::StructDefinition(
+                StructDefinitionError::RecordName(uni_record.get_name().clone()),
+            ));
+        }
+        let mut delay_us = std::mem::take(&mut self.pending_delay_us);
+        if let Some(record_timestamp_us) = self.record_start_timestamp_us {
+            // The timestamps already include the waits of the delay records, keep the longest
+            if let Some(last_operation_timestamp_us) = self.last_operation_timestamp_us {
+                delay_us =
+                    delay_us.max(record_timestamp_us.saturating_sub(last_operation_timestamp_us));
+            }
+            self.last_operation_timestamp_us = Some(record_timestamp_us);
+        }
+        let delay_us = u32::try_from(delay_us).unwrap_or(u32::MAX);
+        if let Some(linter) = self.linter.as_mut() {
+            linter.lint_record(&self.model, &uni_record, self.last_record_start_line);
+        }
+        let source = model::RecordSource {
+            line_nb: self.last_record_start_line,
+            text: self.line_buffer.trim().to_string(),
+        };
+        self.model.add_record(uni_record, delay_us, source);
+        if let (Some(linter), Some(sequence)) = (self.linter.as_mut(), self.model.sequences.last())
+        {
+            linter.lint_sequence_tail(sequence, self.last_record_start_line);
+        }
+        Ok(())
+    }
+
+    /// Declare the enum members and flags of the arguments, structure fields included
+    fn declare_arg_types(&mut self, args: &[UniRecordArgVariant]) -> ParserResult<()> {
+        for arg in args {
+            match arg {
+                UniRecordArgVariant::Identifier(arg) => self
+                    .model
+                    .declare_enum_member(arg)
+                    .map_err(|e| LineParsingError::EnumDiscriminant(Box::new(e)))?,
+                UniRecordArgVariant::Flags(arg) if !self.model.declare_flags(arg) => {
+                    return Err(LineParsingError::TooManyFlags(arg.flags_type.clone()));
+                }
+                UniRecordArgVariant::Struct(arg) => {
+                    self.declare_arg_types(&arg.fields)?;
+                    self.model
+                        .declare_struct(&arg.struct_type, &arg.fields)
+                        .map_err(LineParsingError::StructDefinition)?;
+                }
+                UniRecordArgVariant::ArrayOfStruct(arg) => {
+                    for fields in arg.elements.iter() {
+                        self.declare_arg_types(fields)?;
+                        self.model
+                            .declare_struct(&arg.struct_type, fields)
+                            .map_err(LineParsingError::StructDefinition)?;
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_delay_line_buffer(&mut self) -> ParserResult<()> {
+        let (name, args) = self.read_line_buffer()?.dissassemble();
+        if name != DELAY_RECORD_NAME || args.is_empty() {
+            return Err(LineParsingError::BadDelayRecord);
+        }
+        for arg in args {
+            let value = arg
+                .get_unsigned_value()
+                .ok_or(LineParsingError::BadDelayRecord)?;
+            let delay_us = match arg.get_name().as_str() {
+                "us" => value,
+                "ms" => value.saturating_mul(1_000),
+                _ => return Err(LineParsingError::BadDelayRecord),
+            };
+            self.pending_delay_us = self.pending_delay_us.saturating_add(delay_us);
+        }
+        Ok(())
+    }
+
+    fn parse_record_args(line: &str) -> ParserResult<Vec<UniRecordArgVariant>> {
+        let mut uni_record_args = Vec::new();
+        let args = split_record_args(line);
+        if !args.len().is_multiple_of(2) {
+            return Err(LineParsingError::UncompleteRecordArg);
+        }
+        for arg in args.windows(2).step_by(2) {
+            let arg_name = arg[0];
+            let arg_val = arg[1];
+            let uni_record_arg = UniRecordArgVariant::from(arg_name, arg_val)
+                .map_err(LineParsingError::BadRecordArg)?;
+            uni_record_args.push(uni_record_arg);
+        }
+        Ok(uni_record_args)
+    }
+
+    fn read_line_buffer(&self) -> ParserResult<UniRecord> {
+        let record_str_vec: Vec<&str> = self.line_buffer.splitn(2, ' ').collect();
+        let name = String::from(record_str_vec[0]);
+        let mut args = Vec::new();
+        if record_str_vec.len() >= 2 {
+            args = Self::parse_record_args(record_str_vec[1])?;
+        }
+
+        Ok(UniRecord::new(name, args))
+    }
+
+    fn parse_line_buffer(&mut self) -> ParserResult<()> {
+        let mut uni_record = self.read_line_buffer()?;
+        let is_call = uni_record.get_name() == CALL_RECORD_TAG;
+        // The sequence calls and a logged record cannot share the call operation
+        if (is_call || uni_record.get_name() == CALL_RECORD_NAME)
+            && self.model.defined_records.contains_key(CALL_RECORD_NAME)
+            && self.model.generated_records.contains(CALL_RECORD_NAME) != is_call
+        {
+            return Err(LineParsingError::ReservedCallRecord);
+        }
+        if self
+            .model
+            .expectation_records
+            .contains(uni_record.get_name())
+        {
+            return Err(LineParsingError::ReservedExpectRecord(
+                uni_record.get_name().clone(),
+            ));
+        }
+        if is_call {
+            uni_record = self.read_call_record(uni_record)?;
+        }
+        self.add_record(uni_record)
+    }
+
+    /// Turn a sequence call record into the record of the call operation
+    fn read_call_record(&mut self, uni_record: UniRecord) -> ParserResult<UniRecord> {
+        let (_, args) = uni_record.dissassemble();
+        let [UniRecordArgVariant::Identifier(sequence_id)] = args.as_slice() else {
+            return Err(LineParsingError::BadCallRecord);
+        };
+        self.sequence_calls.push(SequenceCall {
+            caller: self.sequence_name.clone(),
+            callee: sequence_id.value.clone(),
+            line_nb: self.current_line,
+        });
+        self.model
+            .generated_records
+            .insert(CALL_RECORD_NAME.to_string());
+        Ok(UniRecord::new(CALL_RECORD_NAME.to_string(), args))
+    }
+
+    fn parse_expect_line_buffer(&mut self) -> ParserResult<()> {
+        let uni_record = self
+            .read_line_buffer()?
+            .into_expectation()
+            .map_err(LineParsingError::BadRecordArg)?;
+        // The expected-value records and a logged record cannot share the operation
+        let name = uni_record.get_name();
+        if self.model.defined_records.contains_key(name)
+            && !self.model.expectation_records.contains(name)
+        {
+            return Err(LineParsingError::ReservedExpectRecord(name.clone()));
+        }
+        self.model.expectation_records.insert(name.clone());
+        self.add_record(uni_record)
+    }
+
+    fn parse_captured_record(&mut self, delimiter_token_char: char) -> ParserResult<()> {
+        match delimiter_token_char {
+            '?' => self.parse_expect_line_buffer(),
+            _ => self.parse_line_buffer(),
+        }
+    }
+
+    /// Name of the phase delimited by the record content, if it is a phase record
+    fn read_phase_record(record_content: &str) -> ParserResult<Option<String>> {
+        let mut tokens = record_content.split_whitespace();
+        if tokens.next() != Some(PHASE_RECORD_TAG) {
+            return Ok(None);
+        }
+        match (tokens.next(), tokens.next()) {
+            (Some(phase_name), None) => Ok(Some(phase_name.to_string())),
+            _ => Err(LineParsingError::BadPhaseRecord),
+        }
+    }
+
+    fn begin_phase(&mut self, phase_name: String) {
+        let start = self.model.current_sequence().operation_ref_table.len();
+        self.open_phases.push(model::Phase {
+            name: phase_name,
+            start,
+            end: start,
+            phases: Vec::new(),
+        });
+    }
+
+    fn end_phase(&mut self, phase_name: String) -> ParserResult<()> {
+        let mut phase = match self.open_phases.pop() {
+            Some(phase) if phase.name == phase_name => phase,
+            open_phase => {
+                return Err(LineParsingError::UnmatchedPhaseEnd(Box::new((
+                    phase_name,
+                    open_phase.map(|phase| phase.name),
+                ))))
+            }
+        };
+        phase.end = self.model.current_sequence().operation_ref_table.len();
+
+        let sibling_phases = match self.open_phases.last_mut() {
+            Some(parent_phase) => &mut parent_phase.phases,
+            None => &mut self.model.current_sequence().phases,
+        };
+        if sibling_phases
+            .iter()
+            .any(|sibling| sibling.name == phase.name)
+        {
+            return Err(LineParsingError::DuplicatePhase(phase.name));
+        }
+        sibling_phases.push(phase);
+        Ok(())
+    }
+
+    fn one_shot_state(&mut self, mut line: &str) -> ParserResult<bool> {
+        let res = Self::get_delimited_content(&mut line);
+        let mut is_end_of_sequence = false;
+        match res {
+            Ok(delimiter_token_char) => match delimiter_token_char {
+                '=' | '?' => {
+                    self.start_record();
+                    self.line_buffer = line.to_string();
+                    self.parse_captured_record(delimiter_token_char)?;
+                }
+                '~' => {
+                    self.line_buffer = line.to_string();
+                    self.parse_delay_line_buffer()?;
+                }
+                '[' => {
+                    self.start_record();
+                    self.line_buffer = line.to_string();
+                    self.capturing_state = RecordCapturingState::Ranged;
+                }
+                '<' => {
+                    if let Some(phase_name) = Self::read_phase_record(line)? {
+                        self.begin_phase(phase_name);
+                    }
+                }
+                '>' => {
+                    if let Some(phase_name) = Self::read_phase_record(line)? {
+                        self.end_phase(phase_name)?;
+                        return Ok(false);
+                    }
+                    let read_sequence_name = line;
+                    if read_sequence_name != self.sequence_name {
+                        return Err(LineParsingError::UnmatchedNameSequenceStop(Box::new((
+                            String::from(read_sequence_name),
+                            self.sequence_name.clone(),
+                        ))));
+                    }
+                    if let Some(open_phase) = self.open_phases.pop() {
+                        return Err(LineParsingError::UnterminatedPhase(open_phase.name));
+                    }
+                    if self.pending_delay_us > 0 {
+                        return Err(LineParsingError::TrailingDelay);
+                    }
+                    is_end_of_sequence = true;
+                }
+                _ => (),
+            },
+            Err(LineParsingError::MissingEndDelimiter(delimiter_token_char)) => {
+                match delimiter_token_char {
+                    '=' | '?' => {
+                        self.start_record();
+                        self.line_buffer = line.to_string();
+                        self.capturing_state =
+                            RecordCapturingState::Multiline(delimiter_token_char);
+                    }
+                    '[' | '~' => {
+                        return Err(LineParsingError::MissingEndDelimiter(delimiter_token_char))
+                    }
+                    _ => (),
+                }
+            }
+            Err(LineParsingError::UnparsableLine) => (),
+            Err(e) => return Err(e),
+        }
+
+        Ok(is_end_of_sequence)
+    }
+
+    fn multiline_capture_state(
+        &mut self,
+        mut line: &str,
+        record_delimiter: char,
+    ) -> ParserResult<()> {
+        let res = Self::get_delimited_content(&mut line);
+        match res {
+            Ok(delimiter_token_char) => {
+                if delimiter_token_char == record_delimiter {
+                    if self.line_buffer.ends_with(')') {
+                        self.line_buffer += " ";
+                    }
+                    self.line_buffer += line;
+                    self.parse_captured_record(record_delimiter)?;
+                    self.capturing_state = RecordCapturingState::OneShot;
+                }
+            }
+            Err(LineParsingError::MissingBeginDelimiter(delimiter_token_char)) => {
+                if delimiter_token_char == record_delimiter {
+                    if self.line_buffer.ends_with(')') {
+                        self.line_buffer += " ";
+                    }
+                    self.line_buffer += line;
+                    self.parse_captured_record(record_delimiter)?;
+                    self.capturing_state = RecordCapturingState::OneShot;
+                }
+            }
+            Err(LineParsingError::UnparsableLine) => {
+                if self.line_buffer.ends_with(')') {
+                    self.line_buffer += " ";
+                }
+                self.line_buffer += line;
+            }
+            Err(e) => return Err(e),
+        }
+
+        Ok(())
+    }
+
+    fn compare_record_name_to_ref(&mut self, line: &mut &str) -> ParserResult<()> {
+        let tokens: Vec<&str> = line.splitn(2, " ").collect();
+        let record_name = tokens[0];
+        let previous_record_name = self.line_buffer.split(" ").next().unwrap();
+        if record_name != previous_record_name {
+            return Err(LineParsingError::UnmatchedRangedRecordName(Box::new((
+                previous_record_name.to_string(),
+                record_name.to_string(),
+            ))));
+        }
+        if tokens.len() == 2 {
+            *line = tokens[1];
+        }
+        Ok(())
+    }
+
+    fn unterminated_scope_error(&self) -> LineParsingError {
+        let scope_name = self.line_buffer.split(" ").next().unwrap_or_default();
+        LineParsingError::UnterminatedScope(scope_name.to_string())
+    }
+
+    fn ranged_capture_state(&mut self, mut line: &str) -> ParserResult<()> {
+        let res = Self::get_delimited_content(&mut line);
+
+        if let Ok(delimiter_token_char) = res {
+            match delimiter_token_char {
+                '-' => {
+                    if let Ok(()) = self.compare_record_name_to_ref(&mut line) {
+                        self.line_buffer += " ";
+                        self.line_buffer += line;
+                    }
+                }
+                ']' => {
+                    if let Ok(()) = self.compare_record_name_to_ref(&mut line) {
+                        self.capturing_state = RecordCapturingState::OneShot;
+                        self.parse_line_buffer()?;
+                    }
+                }
+                '>' if line == self.sequence_name => {
+                    return Err(self.unterminated_scope_error());
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_delimited_content(line_content: &mut &str) -> ParserResult<char> {
+        let mut has_begin_token = false;
+        let mut has_end_token = false;
+        let mut begin_delimiter_token_char = ' ';
+        let mut end_delimiter_token_char = ' ';
+        let mut range_index_start = 0;
+        let mut range_index_stop = line_content.len();
+
+        let tokens: Vec<&str> = line_content.split(" ").collect();
+        let begin_delimiter = tokens[0];
+        let begin_delimiter_tokens: Vec<&str> = begin_delimiter.split('#').collect();
+        if begin_delimiter_tokens.len() == 2 {
+            let delimiter_token = begin_delimiter_tokens[1];
+            if delimiter_token.chars().count() == 1 {
+                range_index_start += begin_delimiter.len() + 1;
+                begin_delimiter_token_char = delimiter_token.chars().next().unwrap();
+                has_begin_token = true;
+            }
+        }
+
+        let end_delimiter = tokens.last().unwrap();
+        let end_delimiter_tokens: Vec<&str> = end_delimiter.split('#').collect();
+        if end_delimiter_tokens.len() == 2 {
+            let delimiter_token = end_delimiter_tokens[0];
+            if delimiter_token.chars().count() == 1 {
+                end_delimiter_token_char = delimiter_token.chars().next().unwrap();
+                range_index_stop -= end_delimiter.len() + 1;
+                has_end_token = true;
+            }
+        }
+
+        let result_line = &line_content[range_index_start..range_index_stop];
+        match (has_begin_token, has_end_token) {
+            (false, false) => Err(LineParsingError::UnparsableLine),
+            (false, true) => {
+                *line_content = result_line;
+                Err(LineParsingError::MissingBeginDelimiter(
+                    end_delimiter_token_char,
+                ))
+            }
+            (true, false) => {
+                *line_content = result_line;
+                Err(LineParsingError::MissingEndDelimiter(
+                    begin_delimiter_token_char,
+                ))
+            }
+            (true, true) => {
+                *line_content = result_line;
+                if begin_delimiter_token_char != end_delimiter_token_char {
+                    return Err(LineParsingError::UnmatchingRecordDelimiters(
+                        begin_delimiter_token_char,
+                        end_delimiter_token_char,
+                    ));
+                }
+                Ok(begin_delimiter_token_char)
+            }
+        }
+    }
+
+    /// Split the content of a sequence begin record into the sequence name and its metadata,
+    /// written `key=value` or `key="value with spaces"` (eg. `#< init device=rtl8822cs <#`)
+    pub(crate) fn read_sequence_begin(
+        record_content: &str,
+    ) -> ParserResult<(String, Vec<(String, String)>)> {
+        let mut tokens = Vec::new();
+        let mut token = String::new();
+        let mut is_quoted = false;
+        for character in record_content.chars() {
+            match character {
+                '"' => is_quoted = !is_quoted,
+                character if character.is_whitespace() && !is_quoted => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                    continue;
+                }
+                _ => (),
+            }
+            token.push(character);
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+
+        let mut tokens = tokens.into_iter();
+        let sequence_name = tokens.next().unwrap_or_default();
+        let mut metadata: Vec<(String, String)> = Vec::new();
+        for token in tokens {
+            let bad_metadata = || LineParsingError::BadSequenceMetadata(token.clone());
+            let (key, value) = token.split_once('=').ok_or_else(bad_metadata)?;
+            let value = match value.strip_prefix('"') {
+                Some(value) => value.strip_suffix('"').ok_or_else(bad_metadata)?,
+                None => value,
+            };
+            if key.is_empty()
+                || value.contains('"')
+                || metadata.iter().any(|(other_key, _)| other_key == key)
+            {
+                return Err(bad_metadata());
+            }
+            metadata.push((key.to_string(), value.to_string()));
+        }
+        Ok((sequence_name, metadata))
+    }
+
+    fn move_to_begin_token<'a>(
+        lines: &mut impl Iterator<Item = (usize, &'a str)>,
+        timestamp_format: Option<TimestampFormat>,
+    ) -> FileParsingResult<(usize, String)> {
+        // Anything outside of a sequence but its begin record is ignored, as long as it is not a
+        // malformed record
+        for (line_index, line_content) in lines {
+            let (_, mut line_content) = Self::strip_timestamp(timestamp_format, line_content);
+            if !line_content.is_empty() {
+                match Self::get_delimited_content(&mut line_content) {
+                    Ok('<') => return Ok((line_index + 1, line_content.to_string())),
+                    Ok(_) | Err(LineParsingError::UnparsableLine) => (),
+                    Err(line_error) => {
+                        return Err(FileParsingError::LineError {
+                            line_nb: line_index + 1,
+                            line_error,
+                        });
+                    }
+                }
+            }
+        }
+
+        Err(FileParsingError::NoSequenceStart)
+    }
+
+    fn parse_sequence<'a>(
+        &mut self,
+        lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    ) -> FileParsingResult<()> {
+        let mut end_of_parsing = false;
+        self.capturing_state = RecordCapturingState::OneShot;
+        self.last_operation_timestamp_us = None;
+        self.pending_delay_us = 0;
+        self.open_phases.clear();
+
+        for (line_index, line_content) in lines {
+            self.current_line = line_index + 1;
+            let (timestamp_us, line_content) =
+                Self::strip_timestamp(self.timestamp_format, line_content);
+            self.current_timestamp_us = timestamp_us;
+
+            if !line_content.is_empty() {
+                let res = match self.capturing_state {
+                    RecordCapturingState::OneShot => {
+                        let line_parsing_err = self.one_shot_state(line_content);
+                        line_parsing_err.map(|end_of_parsing_| end_of_parsing = end_of_parsing_)
+                    }
+                    RecordCapturingState::Multiline(record_delimiter) => {
+                        self.multiline_capture_state(line_content, record_delimiter)
+                    }
+
+                    RecordCapturingState::Ranged => self.ranged_capture_state(line_content),
+                };
+
+                res.map_err(|e| FileParsingError::LineError {
+                    line_nb: self.current_line,
+                    line_error: e,
+                })?;
+
+                if end_of_parsing {
+                    return Ok(());
+                }
+            }
+        }
+        if let RecordCapturingState::Ranged = self.capturing_state {
+            return Err(FileParsingError::LineError {
+                line_nb: self.last_record_start_line,
+                line_error: self.unterminated_scope_error(),
+            });
+        }
+        Err(FileParsingError::NoSequenceEnd {
+            last_record_start_line: self.last_record_start_line,
+        })
+    }
+
+    /// Look for a cycle in the sequence calls starting from the sequence, returning the
+    /// sequences forming the cycle.
+    fn find_call_cycle(
+        &self,
+        sequence_name: &str,
+        call_stack: &mut Vec<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(position) = call_stack.iter().position(|name| name == sequence_name) {
+            let mut cycle = call_stack[position..].to_vec();
+            cycle.push(sequence_name.to_string());
+            return Some(cycle);
+        }
+
+        call_stack.push(sequence_name.to_string());
+        for sequence_call in self.sequence_calls.iter() {
+            if sequence_call.caller == sequence_name {
+                if let Some(cycle) = self.find_call_cycle(&sequence_call.callee, call_stack) {
+                    return Some(cycle);
+                }
+            }
+        }
+        call_stack.pop();
+        None
+    }
+
+    fn check_sequence_calls(&self) -> FileParsingResult<()> {
+        for sequence_call in self.sequence_calls.iter() {
+            if !self
+                .model
+                .sequence_names()
+                .any(|name| *name == sequence_call.callee)
+            {
+                return Err(FileParsingError::UnknownSequenceCall {
+                    line_nb: sequence_call.line_nb,
+                    sequence_name: sequence_call.callee.clone(),
+                });
+            }
+        }
+
+        for sequence_name in self.model.sequence_names() {
+            if let Some(cycle) = self.find_call_cycle(sequence_name, &mut Vec::new()) {
+                return Err(FileParsingError::RecursiveSequenceCall { cycle });
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse every sequence of the copar log, from their begin to their end delimiter record, to
+    /// create a model.
+    /// Note that this function will pull every byte from the input reader until EOF is met.
+    pub fn parse_file(mut self) -> Result<Model, FileParsingError> {
+        self.parse_sequences()?;
+        Ok(self.model)
+    }
+
+    /// Parse every sequence of the copar log into the model
+    pub(crate) fn parse_sequences(&mut self) -> FileParsingResult<()> {
+        let input_file_buffer = self.input_file_buffer.take().unwrap();
+        let mut lines_it = input_file_buffer.lines().enumerate();
+
+        loop {
+            let (line_nb, sequence_begin) =
+                match Self::move_to_begin_token(&mut lines_it, self.timestamp_format) {
+                    Ok(sequence_begin) => sequence_begin,
+                    Err(FileParsingError::NoSequenceStart) if !self.model.sequences.is_empty() => {
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                };
+
+            let (sequence_name, metadata) =
+                Self::read_sequence_begin(&sequence_begin).map_err(|line_error| {
+                    FileParsingError::LineError {
+                        line_nb,
+                        line_error,
+                    }
+                })?;
+            if self
+                .model
+                .sequence_names()
+                .any(|name| *name == sequence_name)
+            {
+                return Err(FileParsingError::LineError {
+                    line_nb,
+                    line_error: LineParsingError::DuplicateSequence(sequence_name),
+                });
+            }
+            self.sequence_name = sequence_name.clone();
+            self.model.begin_sequence(sequence_name, metadata);
+            self.parse_sequence(&mut lines_it)?;
+        }
+
+        self.check_sequence_calls()
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and Rust versions unlike the std hashers
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,525 @@
+use std::fmt::Display;
+
+use serde::Deserialize;
+
+use crate::model::{
+    check_enum_discriminants, EnumDiscriminantError, EnumMember, Model, StructureDefinitionMember,
+};
+use crate::unirecord::{
+    parse_enum_member, MemberType, RecordParsingError, UniRecord, UniRecordArgVariant,
+};
+
+/// Content of the schema file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    #[serde(rename = "record", default)]
+    records: Vec<RecordDeclaration>,
+    #[serde(rename = "enum", default)]
+    enums: Vec<EnumDeclaration>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecordDeclaration {
+    name: String,
+    #[serde(default)]
+    args: Vec<ArgDeclaration>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArgDeclaration {
+    name: String,
+    #[serde(rename = "type")]
+    arg_type: String,
+    /// Value of the argument when it is absent from the record, making the argument optional
+    default: Option<String>,
+}
+
+#[derive(Debug)]
+struct ArgSchema {
+    name: String,
+    member_type: MemberType,
+    default: Option<UniRecordArgVariant>,
+}
+
+#[derive(Debug)]
+struct RecordSchema {
+    name: String,
+    args: Vec<ArgSchema>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EnumDeclaration {
+    name: String,
+    /// Values of the enum, written `value` or `value=discriminant`
+    values: Vec<String>,
+}
+
+#[derive(Debug)]
+struct EnumSchema {
+    name: String,
+    members: Vec<EnumMember>,
+}
+
+/// Error of a schema file
+#[derive(Debug)]
+pub enum SchemaError {
+    BadFormat(toml::de::Error),
+    DuplicateRecord(String),
+    DuplicateArg {
+        record_name: String,
+        arg_name: String,
+    },
+    DuplicateEnum(String),
+    BadEnumValue {
+        enum_type: String,
+        error: RecordParsingError,
+    },
+    EnumDiscriminant(EnumDiscriminantError),
+    BadArgType {
+        record_name: String,
+        arg_name: String,
+        error: RecordParsingError,
+    },
+    BadDefaultValue {
+        record_name: String,
+        arg_name: String,
+        error: RecordParsingError,
+    },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadFormat(e) => write!(f, "{e}"),
+            Self::DuplicateRecord(record_name) => {
+                write!(f, "Record `{record_name}` is declared twice")
+            }
+            Self::DuplicateArg {
+                record_name,
+                arg_name,
+            } => write!(
+                f,
+                "Argument `{arg_name}` of record `{record_name}` is declared twice"
+            ),
+            Self::DuplicateEnum(enum_type) => write!(f, "Enum `{enum_type}` is declared twice"),
+            Self::BadEnumValue { enum_type, error } => {
+                write!(f, "Bad value of enum `{enum_type}`: {error}")
+            }
+            Self::EnumDiscriminant(e) => write!(f, "{e}"),
+            Self::BadArgType {
+                record_name,
+                arg_name,
+                error,
+            } => write!(
+                f,
+                "Bad type of argument `{arg_name}` of record `{record_name}`: {error}"
+            ),
+            Self::BadDefaultValue {
+                record_name,
+                arg_name,
+                error,
+            } => write!(
+                f,
+                "Bad default value of argument `{arg_name}` of record `{record_name}`: {error}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Record not complying with the schema
+#[derive(Debug, PartialEq)]
+pub enum SchemaViolation {
+    UnknownRecord(String),
+    UnknownArg {
+        record_name: String,
+        arg_name: String,
+    },
+    MissingArg {
+        record_name: String,
+        arg_name: String,
+    },
+    BadArgType {
+        record_name: String,
+        arg_name: String,
+        expected_type: String,
+        arg_type: String,
+    },
+    UnknownEnumValue {
+        enum_type: String,
+        value: String,
+    },
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownRecord(record_name) => {
+                write!(f, "Record `{record_name}` is not declared in the schema")
+            }
+            Self::UnknownArg {
+                record_name,
+                arg_name,
+            } => write!(
+                f,
+                "Argument `{arg_name}` is not declared for record `{record_name}` in the schema"
+            ),
+            Self::MissingArg {
+                record_name,
+                arg_name,
+            } => write!(
+                f,
+                "Record `{record_name}` has no argument `{arg_name}`, required by the schema"
+            ),
+            Self::BadArgType {
+                record_name,
+                arg_name,
+                expected_type,
+                arg_type,
+            } => write!(
+                f,
+                "Argument `{arg_name}` of record `{record_name}` has type `{arg_type}` but the schema declares `{expected_type}`"
+            ),
+            Self::UnknownEnumValue { enum_type, value } => write!(
+                f,
+                "Value `{value}` is not declared for enum `{enum_type}` in the schema"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaViolation {}
+
+/// Declaration of the records and enums of the logs, read from a TOML schema file. The parser
+/// validates every record against it, and the generators emit the records and enums in the
+/// order of the schema.
+#[derive(Debug)]
+pub struct Schema {
+    records: Vec<RecordSchema>,
+    enums: Vec<EnumSchema>,
+}
+
+impl ArgSchema {
+    fn from_declaration(
+        record_name: &str,
+        arg_declaration: ArgDeclaration,
+    ) -> Result<ArgSchema, SchemaError> {
+        let ArgDeclaration {
+            name,
+            arg_type,
+            default,
+        } = arg_declaration;
+        let member_type: MemberType =
+            arg_type.parse().map_err(|error| SchemaError::BadArgType {
+                record_name: record_name.to_string(),
+                arg_name: name.clone(),
+                error,
+            })?;
+        let default = default
+            .map(|default| {
+                // The default value is read as the value of the argument in a record
+                let arg_value = match &member_type {
+                    MemberType::Identifier(enum_type) => format!("id({enum_type}::{default})"),
+                    MemberType::Flags(flags_type) if default.is_empty() => {
+                        format!("flags({flags_type})")
+                    }
+                    MemberType::Flags(flags_type) => {
+                        let flags: Vec<String> = default
+                            .split('|')
+                            .map(|flag| format!("{flags_type}::{flag}"))
+                            .collect();
+                        format!("flags({})", flags.join("|"))
+                    }
+                    member_type => {
+                        format!(
+                            "{}({default})",
+                            member_type.to_string().trim_end_matches("[]")
+                        )
+                    }
+                };
+                let default = UniRecordArgVariant::from(&format!("{name}:"), &arg_value)?;
+                if default.get_type() != member_type {
+                    return Err(RecordParsingError::UnexpectedArgType(default.get_type()));
+                }
+                Ok(default)
+            })
+            .transpose()
+            .map_err(|error| SchemaError::BadDefaultValue {
+                record_name: record_name.to_string(),
+                arg_name: name.clone(),
+                error,
+            })?;
+        Ok(ArgSchema {
+            name,
+            member_type,
+            default,
+        })
+    }
+}
+
+impl Schema {
+    /// Read the schema from the content of a TOML schema file
+    pub fn from_toml(content: &str) -> Result<Schema, SchemaError> {
+        let schema_file: SchemaFile = toml::from_str(content).map_err(SchemaError::BadFormat)?;
+
+        let mut records: Vec<RecordSchema> = Vec::new();
+        for record_declaration in schema_file.records {
+            let record_name = record_declaration.name;
+            if records.iter().any(|record| record.name == record_name) {
+                return Err(SchemaError::DuplicateRecord(record_name));
+            }
+            let mut args: Vec<ArgSchema> = Vec::new();
+            for arg_declaration in record_declaration.args {
+                let arg = ArgSchema::from_declaration(&record_name, arg_declaration)?;
+                if args
+                    .iter()
+                    .any(|declared_arg| declared_arg.name == arg.name)
+                {
+                    return Err(SchemaError::DuplicateArg {
+                        record_name,
+                        arg_name: arg.name,
+                    });
+                }
+                args.push(arg);
+            }
+            records.push(RecordSchema {
+                name: record_name,
+                args,
+            });
+        }
+
+        let mut enums: Vec<EnumSchema> = Vec::new();
+        for enum_declaration in schema_file.enums {
+            let enum_type = enum_declaration.name;
+            if enums.iter().any(|declared| declared.name == enum_type) {
+                return Err(SchemaError::DuplicateEnum(enum_type));
+            }
+            let members = enum_declaration
+                .values
+                .iter()
+                .map(|value| {
+                    parse_enum_member(value)
+                        .map(|(name, discriminant)| EnumMember { name, discriminant })
+                })
+                .collect::<Result<Vec<EnumMember>, RecordParsingError>>()
+                .map_err(|error| SchemaError::BadEnumValue {
+                    enum_type: enum_type.clone(),
+                    error,
+                })?;
+            check_enum_discriminants(&enum_type, &members)
+                .map_err(SchemaError::EnumDiscriminant)?;
+            enums.push(EnumSchema {
+                name: enum_type,
+                members,
+            });
+        }
+        Ok(Schema { records, enums })
+    }
+
+    fn check_enum_value(&self, arg: &UniRecordArgVariant) -> Result<(), SchemaViolation> {
+        let UniRecordArgVariant::Identifier(arg) = arg else {
+            return Ok(());
+        };
+        // Only the declared enums are restricted to their values
+        match self
+            .enums
+            .iter()
+            .find(|enum_schema| enum_schema.name == arg.enum_type)
+        {
+            Some(enum_schema)
+                if !enum_schema
+                    .members
+                    .iter()
+                    .any(|member| member.name == arg.value) =>
+            {
+                Err(SchemaViolation::UnknownEnumValue {
+                    enum_type: arg.enum_type.clone(),
+                    value: arg.value.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the record against its declaration, returning it with its arguments in the order of
+    /// the schema and the absent optional arguments set to their default value.
+    pub(crate) fn validate(&self, record: UniRecord) -> Result<UniRecord, SchemaViolation> {
+        let (record_name, record_args) = record.dissassemble();
+        let Some(record_schema) = self
+            .records
+            .iter()
+            .find(|record_schema| record_schema.name == record_name)
+        else {
+            return Err(SchemaViolation::UnknownRecord(record_name));
+        };
+
+        let mut ordered_args: Vec<Option<UniRecordArgVariant>> =
+            record_schema.args.iter().map(|_| None).collect();
+        for arg in record_args {
+            let Some(index) = record_schema
+                .args
+                .iter()
+                .position(|arg_schema| arg_schema.name == *arg.get_name())
+            else {
+                return Err(SchemaViolation::UnknownArg {
+                    record_name,
+                    arg_name: arg.get_name().clone(),
+                });
+            };
+            let arg_schema = &record_schema.args[index];
+            if arg.get_type() != arg_schema.member_type {
+                return Err(SchemaViolation::BadArgType {
+                    record_name,
+                    arg_name: arg_schema.name.clone(),
+                    expected_type: arg_schema.member_type.to_string(),
+                    arg_type: arg.get_type().to_string(),
+                });
+            }
+            self.check_enum_value(&arg)?;
+            ordered_args[index] = Some(arg);
+        }
+
+        let args = ordered_args
+            .into_iter()
+            .zip(record_schema.args.iter())
+            .map(|(arg, arg_schema)| {
+                arg.or_else(|| arg_schema.default.clone()).ok_or_else(|| {
+                    SchemaViolation::MissingArg {
+                        record_name: record_name.clone(),
+                        arg_name: arg_schema.name.clone(),
+                    }
+                })
+            })
+            .collect::<Result<Vec<UniRecordArgVariant>, SchemaViolation>>()?;
+        Ok(UniRecord::new(record_name, args))
+    }
+}
+
+impl Model {
+    /// Define the records and enums of the schema, so that the generators emit them in the order
+    /// of the schema whatever the order of the log.
+    pub(crate) fn declare_schema(&mut self, schema: &Schema) {
+        for record_schema in schema.records.iter() {
+            let members = record_schema
+                .args
+                .iter()
+                .map(|arg_schema| StructureDefinitionMember {
+                    member_name: arg_schema.name.clone(),
+                    member_type: arg_schema.member_type.clone(),
+                })
+                .collect();
+            self.defined_records
+                .insert(record_schema.name.clone(), members);
+        }
+        for enum_schema in schema.enums.iter() {
+            self.defined_enums
+                .insert(enum_schema.name.clone(), enum_schema.members.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+[[record]]
+name = "Cmd52"
+args = [
+    { name = "add", type = "x32" },
+    { name = "data", type = "x8", default = "0x0" },
+    { name = "mode", type = "id(Mode)" },
+]
+
+[[enum]]
+name = "Mode"
+values = ["slow", "fast=0x4"]
+"#;
+
+    fn record(record_content: &str) -> UniRecord {
+        let (name, args) = record_content.split_once(' ').unwrap();
+        let args = args
+            .split(' ')
+            .collect::<Vec<&str>>()
+            .chunks(2)
+            .map(|arg| UniRecordArgVariant::from(arg[0], arg[1]).unwrap())
+            .collect();
+        UniRecord::new(name.to_string(), args)
+    }
+
+    #[test]
+    fn schema_validation_test() {
+        let schema = Schema::from_toml(SCHEMA).unwrap();
+
+        assert_eq!(
+            schema.validate(record("Cmd52 mode: id(Mode::fast) add: x32(0x10)")),
+            Ok(record(
+                "Cmd52 add: x32(0x10) data: x8(0x0) mode: id(Mode::fast)"
+            ))
+        );
+        assert_eq!(
+            schema.validate(record("Cmd53 add: x32(0x10)")),
+            Err(SchemaViolation::UnknownRecord(String::from("Cmd53")))
+        );
+        assert!(matches!(
+            schema.validate(record(
+                "Cmd52 add: x32(0x10) mode: id(Mode::fast) ln: u8(1)"
+            )),
+            Err(SchemaViolation::UnknownArg { .. })
+        ));
+        assert!(matches!(
+            schema.validate(record("Cmd52 mode: id(Mode::fast)")),
+            Err(SchemaViolation::MissingArg { .. })
+        ));
+        assert!(matches!(
+            schema.validate(record("Cmd52 add: x16(0x10) mode: id(Mode::fast)")),
+            Err(SchemaViolation::BadArgType { .. })
+        ));
+        assert!(matches!(
+            schema.validate(record("Cmd52 add: x32(0x10) mode: id(Mode::turbo)")),
+            Err(SchemaViolation::UnknownEnumValue { .. })
+        ));
+
+        let bad_default = SCHEMA.replace("0x0", "0x100");
+        assert!(matches!(
+            Schema::from_toml(&bad_default),
+            Err(SchemaError::BadDefaultValue { .. })
+        ));
+        let array_default = SCHEMA.replace(
+            r#""x8", default = "0x0""#,
+            r#""x8[]", default = "[0x1,0x2]""#,
+        );
+        let schema = Schema::from_toml(&array_default).unwrap();
+        assert_eq!(
+            schema.validate(record("Cmd52 add: x32(0x10) mode: id(Mode::fast)")),
+            Ok(record(
+                "Cmd52 add: x32(0x10) data: x8([0x1,0x2]) mode: id(Mode::fast)"
+            ))
+        );
+        let scalar_array_default = SCHEMA.replace(r#""x8", default"#, r#""x8[]", default"#);
+        assert!(matches!(
+            Schema::from_toml(&scalar_array_default),
+            Err(SchemaError::BadDefaultValue {
+                error: RecordParsingError::UnexpectedArgType(MemberType::X8),
+                ..
+            })
+        ));
+        let duplicate_discriminant = SCHEMA.replace("0x4", "0x0");
+        assert!(matches!(
+            Schema::from_toml(&duplicate_discriminant),
+            Err(SchemaError::EnumDiscriminant(
+                EnumDiscriminantError::Duplicate { .. }
+            ))
+        ));
+        let bad_type = SCHEMA.replace("x32", "x33");
+        assert!(matches!(
+            Schema::from_toml(&bad_type),
+            Err(SchemaError::BadArgType { .. })
+        ));
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::model::{ArrayInstanceVariant, Model, OperationParameterVariant, StructInstanceVariant};
+use crate::unirecord::MemberType;
+
+/// Size of the C enums, as laid out by the usual embedded compilers
+const C_ENUM_SIZE: usize = 4;
+/// Size of the delays of the C delay table (`uint32_t`)
+const C_DELAY_SIZE: usize = 4;
+
+/// Operation counts of a record type
+#[derive(Debug)]
+pub struct RecordStats {
+    pub name: String,
+    /// Number of entries of the operation tables using this record
+    pub operation_count: usize,
+    /// Number of distinct instances of this record after deduplication
+    pub instance_count: usize,
+    /// Size of the generated C structure
+    pub c_struct_size: usize,
+}
+
+/// Summary of a parsed log, giving the size of the generated tables
+#[derive(Debug)]
+pub struct ModelStats {
+    /// Name and number of operations of each sequence
+    pub sequences: Vec<(String, usize)>,
+    /// Number of entries of all the operation tables
+    pub operation_count: usize,
+    /// Number of distinct operation instances after deduplication
+    pub instance_count: usize,
+    pub records: Vec<RecordStats>,
+    /// Number of distinct arrays after deduplication
+    pub array_count: usize,
+    /// Size of the elements of all the distinct arrays
+    pub array_element_bytes: usize,
+    /// Name and number of values of each enum
+    pub enums: Vec<(String, usize)>,
+    /// Estimated size of the constant data generated in C
+    pub c_rom_bytes: usize,
+    /// Estimated size of the constant data generated in C if no operation or array was
+    /// deduplicated
+    pub c_rom_bytes_without_dedup: usize,
+}
+
+impl Display for ModelStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Sequences:")?;
+        for (sequence_name, operation_count) in self.sequences.iter() {
+            writeln!(f, "  {sequence_name}: {operation_count} operations")?;
+        }
+        writeln!(
+            f,
+            "Operations: {}, unique instances: {}",
+            self.operation_count, self.instance_count
+        )?;
+        writeln!(f, "Records:")?;
+        for record in self.records.iter() {
+            writeln!(
+                f,
+                "  {}: {} operations, {} instances, {} bytes each",
+                record.name, record.operation_count, record.instance_count, record.c_struct_size
+            )?;
+        }
+        writeln!(
+            f,
+            "Arrays: {}, {} element bytes",
+            self.array_count, self.array_element_bytes
+        )?;
+        writeln!(f, "Enums:")?;
+        for (enum_name, value_count) in self.enums.iter() {
+            writeln!(f, "  {enum_name}: {value_count} values")?;
+        }
+        write!(
+            f,
+            "Estimated C ROM footprint: {} bytes ({} bytes without deduplication)",
+            self.c_rom_bytes, self.c_rom_bytes_without_dedup
+        )
+    }
+}
+
+/// Size and alignment of a C type. The 128-bit integers are the structures of two 64-bit words
+/// of the generated header without `COPAR_NATIVE_INT128`, aligned on 8 bytes.
+fn c_member_layout(member_type: &MemberType, c_pointer_size: usize) -> (usize, usize) {
+    let size = match member_type {
+        MemberType::X8 | MemberType::U8 | MemberType::I8 | MemberType::Bool => 1,
+        MemberType::X16 | MemberType::U16 | MemberType::I16 => 2,
+        MemberType::X32
+        | MemberType::U32
+        | MemberType::I32
+        | MemberType::F32
+        | MemberType::Flags(_) => 4,
+        MemberType::X64 | MemberType::U64 | MemberType::I64 | MemberType::F64 => 8,
+        MemberType::X128 | MemberType::U128 | MemberType::I128 => return (16, 8),
+        MemberType::Identifier(_) => C_ENUM_SIZE,
+        _ => c_pointer_size,
+    };
+    (size, size)
+}
+
+/// Size of a C structure of members of the given sizes and alignments
+fn c_struct_size(member_layouts: impl Iterator<Item = (usize, usize)>) -> usize {
+    let mut size: usize = 0;
+    let mut alignment = 1;
+    for (member_size, member_alignment) in member_layouts {
+        size = size.next_multiple_of(member_alignment);
+        size += member_size;
+        alignment = alignment.max(member_alignment);
+    }
+    size.next_multiple_of(alignment)
+}
+
+fn array_element_bytes(array: &ArrayInstanceVariant) -> usize {
+    match array {
+        ArrayInstanceVariant::X8(values) => values.len(),
+        ArrayInstanceVariant::U8(values) => values.len(),
+        ArrayInstanceVariant::I8(values) => values.len(),
+        ArrayInstanceVariant::X16(values) => values.len() * 2,
+        ArrayInstanceVariant::U16(values) => values.len() * 2,
+        ArrayInstanceVariant::I16(values) => values.len() * 2,
+        ArrayInstanceVariant::X32(values) => values.len() * 4,
+        ArrayInstanceVariant::U32(values) => values.len() * 4,
+        ArrayInstanceVariant::I32(values) => values.len() * 4,
+        ArrayInstanceVariant::F32(values) => values.len() * 4,
+        ArrayInstanceVariant::X64(values) => values.len() * 8,
+        ArrayInstanceVariant::U64(values) => values.len() * 8,
+        ArrayInstanceVariant::I64(values) => values.len() * 8,
+        ArrayInstanceVariant::X128(values) => values.len() * 16,
+        ArrayInstanceVariant::U128(values) => values.len() * 16,
+        ArrayInstanceVariant::I128(values) => values.len() * 16,
+        ArrayInstanceVariant::F64(values) => values.len() * 8,
+    }
+}
+
+impl Model {
+    /// Summarize the model, estimating the size of the C tables for a target whose pointers are
+    /// `c_pointer_size` bytes long.
+    pub fn stats(&self, c_pointer_size: usize) -> ModelStats {
+        let record_sizes: HashMap<&String, usize> = self
+            .defined_records
+            .iter()
+            .map(|(record_name, members)| {
+                let member_layouts = members
+                    .iter()
+                    .map(|member| c_member_layout(&member.member_type, c_pointer_size));
+                (record_name, c_struct_size(member_layouts))
+            })
+            .collect();
+        // Nested structure instances are shared by the operations, counted once
+        let struct_instance_bytes: usize = self
+            .struct_instances
+            .keys()
+            .map(|instance| match instance {
+                StructInstanceVariant::Struct { struct_type, .. } => {
+                    let member_layouts = self.defined_structs[struct_type]
+                        .iter()
+                        .map(|member| c_member_layout(&member.member_type, c_pointer_size));
+                    c_struct_size(member_layouts)
+                }
+                StructInstanceVariant::Array { instances, .. } => instances.len() * c_pointer_size,
+            })
+            .sum();
+        let array_bytes: HashMap<&String, usize> = self
+            .instanciated_arrays
+            .iter()
+            .map(|(array, array_name)| (array_name, array_element_bytes(array)))
+            .collect();
+        // Size of each operation instance with the arrays it references
+        let instance_bytes: HashMap<&String, usize> = self
+            .operation_instances
+            .iter()
+            .map(|(operation, instance_name)| {
+                let referenced_array_bytes: usize = operation
+                    .parameters
+                    .iter()
+                    .filter_map(OperationParameterVariant::array_instance_name)
+                    .map(|array_name| array_bytes[array_name])
+                    .sum();
+                (
+                    instance_name,
+                    record_sizes[&operation.operation_type] + referenced_array_bytes,
+                )
+            })
+            .collect();
+
+        let mut records: Vec<RecordStats> = self
+            .defined_records
+            .keys()
+            .map(|record_name| RecordStats {
+                name: record_name.clone(),
+                operation_count: 0,
+                instance_count: self
+                    .operation_instances
+                    .keys()
+                    .filter(|operation| operation.operation_type == *record_name)
+                    .count(),
+                c_struct_size: record_sizes[record_name],
+            })
+            .collect();
+
+        let operation_entry_size = c_struct_size(
+            [(C_ENUM_SIZE, C_ENUM_SIZE), (c_pointer_size, c_pointer_size)].into_iter(),
+        );
+        let mut table_bytes = 0;
+        let mut bytes_without_dedup = 0;
+        for sequence in self.sequences.iter() {
+            table_bytes += sequence.operation_ref_table.len() * operation_entry_size;
+            if sequence.has_timing() {
+                table_bytes += sequence.operation_ref_table.len() * C_DELAY_SIZE;
+            }
+            for operation_table_member in sequence.operation_ref_table.iter() {
+                bytes_without_dedup +=
+                    instance_bytes[&operation_table_member.operation_variant_ref_name];
+                if let Some(record) = records
+                    .iter_mut()
+                    .find(|record| record.name == operation_table_member.operation_type)
+                {
+                    record.operation_count += 1;
+                }
+            }
+        }
+        let array_element_bytes: usize = array_bytes.values().sum();
+        let distinct_instance_bytes: usize = records
+            .iter()
+            .map(|record| record.instance_count * record.c_struct_size)
+            .sum();
+
+        ModelStats {
+            sequences: self
+                .sequences
+                .iter()
+                .map(|sequence| (sequence.name.clone(), sequence.operation_ref_table.len()))
+                .collect(),
+            operation_count: self
+                .sequences
+                .iter()
+                .map(|sequence| sequence.operation_ref_table.len())
+                .sum(),
+            instance_count: self.operation_instances.len(),
+            records,
+            array_count: self.instanciated_arrays.len(),
+            array_element_bytes,
+            enums: self
+                .defined_enums
+                .iter()
+                .map(|(enum_name, values)| (enum_name.clone(), values.len()))
+                .collect(),
+            c_rom_bytes: table_bytes
+                + distinct_instance_bytes
+                + array_element_bytes
+                + struct_instance_bytes,
+            c_rom_bytes_without_dedup: table_bytes + bytes_without_dedup + struct_instance_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Parser;
+
+    #[test]
+    fn stats_test() {
+        let log = "\
+#< init <#
+#= Cmd52 add: x32(0x10) data: x8(0x1) =#
+#= Cmd52 add: x32(0x10) data: x8(0x1) =#
+#= Cmd53 add: x32(0x10) data: x8([0x1,0x2,0x3]) mode: id(Mode::fast) =#
+#= Cmd53 add: x32(0x10) data: x8([0x1,0x2,0x3]) mode: id(Mode::slow) =#
+#> init >#
+";
+        let model = Parser::parse(log.as_bytes()).unwrap();
+        let stats = model.stats(4);
+
+        assert_eq!(stats.operation_count, 4);
+        assert_eq!(stats.instance_count, 3);
+        assert_eq!(stats.array_count, 1);
+        assert_eq!(stats.array_element_bytes, 3);
+        assert_eq!(stats.enums, [(String::from("Mode"), 2)]);
+        assert_eq!(stats.records[0].c_struct_size, 8);
+        assert_eq!(stats.records[1].c_struct_size, 12);
+        // 4 entries of 8 bytes, 2 Cmd53 and a Cmd52 instance, 3 array bytes
+        assert_eq!(stats.c_rom_bytes, 32 + 2 * 12 + 8 + 3);
+        assert_eq!(stats.c_rom_bytes_without_dedup, 32 + 2 * 8 + 2 * (12 + 3));
+
+        // The 128-bit words are aligned on 8 bytes
+        let log = "\
+#< init <#
+#= Key slot: u32(1) key: x128(0x1) =#
+#> init >#
+";
+        let stats = Parser::parse(log.as_bytes()).unwrap().stats(4);
+        assert_eq!(stats.records[0].c_struct_size, 24);
+    }
+}
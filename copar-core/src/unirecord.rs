@@ -0,0 +1,1522 @@
+use crate::literal::{parse_integer, LiteralError};
+use std::{fmt::Display, num::ParseFloatError, str::FromStr};
+use stringcase::pascal_case;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberType {
+    X8,
+    X16,
+    X32,
+    X64,
+    X128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    ArrayOfX8,
+    ArrayOfX16,
+    ArrayOfX32,
+    ArrayOfX64,
+    ArrayOfX128,
+    ArrayOfU8,
+    ArrayOfU16,
+    ArrayOfU32,
+    ArrayOfU64,
+    ArrayOfU128,
+    ArrayOfI8,
+    ArrayOfI16,
+    ArrayOfI32,
+    ArrayOfI64,
+    ArrayOfI128,
+    ArrayOfF32,
+    ArrayOfF64,
+    Bool,
+    Identifier(String),
+    Flags(String),
+    Struct(String),
+    ArrayOfStruct(String),
+}
+
+impl Display for MemberType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::X8 => write!(f, "x8"),
+            Self::X16 => write!(f, "x16"),
+            Self::X32 => write!(f, "x32"),
+            Self::X64 => write!(f, "x64"),
+            Self::X128 => write!(f, "x128"),
+            Self::U8 => write!(f, "u8"),
+            Self::U16 => write!(f, "u16"),
+            Self::U32 => write!(f, "u32"),
+            Self::U64 => write!(f, "u64"),
+            Self::U128 => write!(f, "u128"),
+            Self::I8 => write!(f, "i8"),
+            Self::I16 => write!(f, "i16"),
+            Self::I32 => write!(f, "i32"),
+            Self::I64 => write!(f, "i64"),
+            Self::I128 => write!(f, "i128"),
+            Self::F32 => write!(f, "f32"),
+            Self::F64 => write!(f, "f64"),
+            Self::ArrayOfX8 => write!(f, "x8[]"),
+            Self::ArrayOfX16 => write!(f, "x16[]"),
+            Self::ArrayOfX32 => write!(f, "x32[]"),
+            Self::ArrayOfX64 => write!(f, "x64[]"),
+            Self::ArrayOfX128 => write!(f, "x128[]"),
+            Self::ArrayOfU8 => write!(f, "u8[]"),
+            Self::ArrayOfU16 => write!(f, "u16[]"),
+            Self::ArrayOfU32 => write!(f, "u32[]"),
+            Self::ArrayOfU64 => write!(f, "u64[]"),
+            Self::ArrayOfU128 => write!(f, "u128[]"),
+            Self::ArrayOfI8 => write!(f, "i8[]"),
+            Self::ArrayOfI16 => write!(f, "i16[]"),
+            Self::ArrayOfI32 => write!(f, "i32[]"),
+            Self::ArrayOfI64 => write!(f, "i64[]"),
+            Self::ArrayOfI128 => write!(f, "i128[]"),
+            Self::ArrayOfF32 => write!(f, "f32[]"),
+            Self::ArrayOfF64 => write!(f, "f64[]"),
+            Self::Bool => write!(f, "bool"),
+            Self::Identifier(enum_type) => write!(f, "id({enum_type})"),
+            Self::Flags(flags_type) => write!(f, "flags({flags_type})"),
+            Self::Struct(struct_type) => write!(f, "struct({struct_type})"),
+            Self::ArrayOfStruct(struct_type) => write!(f, "struct({struct_type})[]"),
+        }
+    }
+}
+
+impl FromStr for MemberType {
+    type Err = RecordParsingError;
+
+    /// Read a type written as by `Display` (eg. `x8`, `x8[]`, `id(EfuseAccess)`,
+    /// `flags(CtrlBits)`, `struct(DmaDesc)[]`)
+    fn from_str(member_type: &str) -> Result<MemberType, RecordParsingError> {
+        let member_type = match member_type {
+            "x8" => Self::X8,
+            "x16" => Self::X16,
+            "x32" => Self::X32,
+            "x64" => Self::X64,
+            "x128" => Self::X128,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "u128" => Self::U128,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "i128" => Self::I128,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            "x8[]" => Self::ArrayOfX8,
+            "x16[]" => Self::ArrayOfX16,
+            "x32[]" => Self::ArrayOfX32,
+            "x64[]" => Self::ArrayOfX64,
+            "x128[]" => Self::ArrayOfX128,
+            "u8[]" => Self::ArrayOfU8,
+            "u16[]" => Self::ArrayOfU16,
+            "u32[]" => Self::ArrayOfU32,
+            "u64[]" => Self::ArrayOfU64,
+            "u128[]" => Self::ArrayOfU128,
+            "i8[]" => Self::ArrayOfI8,
+            "i16[]" => Self::ArrayOfI16,
+            "i32[]" => Self::ArrayOfI32,
+            "i64[]" => Self::ArrayOfI64,
+            "i128[]" => Self::ArrayOfI128,
+            "f32[]" => Self::ArrayOfF32,
+            "f64[]" => Self::ArrayOfF64,
+            "bool" => Self::Bool,
+            _ => {
+                let type_parameter = |prefix: &str| {
+                    member_type
+                        .strip_prefix(prefix)
+                        .and_then(|type_name| type_name.strip_suffix(')'))
+                        .map(str::to_string)
+                };
+                if let Some(enum_type) = type_parameter("id(") {
+                    Self::Identifier(enum_type)
+                } else if let Some(flags_type) = type_parameter("flags(") {
+                    Self::Flags(flags_type)
+                } else if let Some(struct_type) = member_type
+                    .strip_suffix("[]")
+                    .and_then(|struct_array_type| struct_array_type.strip_prefix("struct("))
+                    .and_then(|struct_type| struct_type.strip_suffix(')'))
+                {
+                    Self::ArrayOfStruct(struct_type.to_string())
+                } else if let Some(struct_type) = type_parameter("struct(") {
+                    Self::Struct(struct_type)
+                } else {
+                    return Err(RecordParsingError::UnknownArgType(member_type.to_string()));
+                }
+            }
+        };
+        Ok(member_type)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UniRecordArgVariant {
+    X8(UniRecordArg<u8>),
+    X16(UniRecordArg<u16>),
+    X32(UniRecordArg<u32>),
+    X64(UniRecordArg<u64>),
+    X128(UniRecordArg<u128>),
+    U8(UniRecordArg<u8>),
+    U16(UniRecordArg<u16>),
+    U32(UniRecordArg<u32>),
+    U64(UniRecordArg<u64>),
+    U128(UniRecordArg<u128>),
+    I8(UniRecordArg<i8>),
+    I16(UniRecordArg<i16>),
+    I32(UniRecordArg<i32>),
+    I64(UniRecordArg<i64>),
+    I128(UniRecordArg<i128>),
+    F32(UniRecordArg<f32>),
+    F64(UniRecordArg<f64>),
+    ArrayOfX8(UniRecordArg<Vec<u8>>),
+    ArrayOfX16(UniRecordArg<Vec<u16>>),
+    ArrayOfX32(UniRecordArg<Vec<u32>>),
+    ArrayOfX64(UniRecordArg<Vec<u64>>),
+    ArrayOfX128(UniRecordArg<Vec<u128>>),
+    ArrayOfU8(UniRecordArg<Vec<u8>>),
+    ArrayOfU16(UniRecordArg<Vec<u16>>),
+    ArrayOfU32(UniRecordArg<Vec<u32>>),
+    ArrayOfU64(UniRecordArg<Vec<u64>>),
+    ArrayOfU128(UniRecordArg<Vec<u128>>),
+    ArrayOfI8(UniRecordArg<Vec<i8>>),
+    ArrayOfI16(UniRecordArg<Vec<i16>>),
+    ArrayOfI32(UniRecordArg<Vec<i32>>),
+    ArrayOfI64(UniRecordArg<Vec<i64>>),
+    ArrayOfI128(UniRecordArg<Vec<i128>>),
+    ArrayOfF32(UniRecordArg<Vec<f32>>),
+    ArrayOfF64(UniRecordArg<Vec<f64>>),
+    Bool(UniRecordArg<bool>),
+    Identifier(IdentifierRecordArg),
+    Flags(FlagsRecordArg),
+    Struct(StructRecordArg),
+    ArrayOfStruct(StructArrayRecordArg),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdentifierRecordArg {
+    pub(crate) name: String,
+    pub(crate) enum_type: String,
+    pub(crate) value: String,
+    /// Numeric value of the enum member, given as `id(EnumType::value=0x3)`
+    pub(crate) discriminant: Option<u32>,
+}
+
+/// Combination of named bits, written `flags(CtrlBits::EN|CtrlBits::IRQ)`, or `flags(CtrlBits)`
+/// when no bit is set
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagsRecordArg {
+    pub(crate) name: String,
+    pub(crate) flags_type: String,
+    pub(crate) flags: Vec<String>,
+}
+
+impl FlagsRecordArg {
+    pub fn dissassemble(self) -> (String, String, Vec<String>) {
+        (self.name, self.flags_type, self.flags)
+    }
+
+    fn from(name: String, value: &str) -> Result<FlagsRecordArg, RecordParsingError> {
+        if !value.contains("::") {
+            if value.is_empty() {
+                return Err(RecordParsingError::BadFlagsFormat());
+            }
+            return Ok(FlagsRecordArg {
+                name,
+                flags_type: value.to_string(),
+                flags: Vec::new(),
+            });
+        }
+        let mut flags_type = None;
+        let mut flags = Vec::new();
+        for flag in value.split('|') {
+            let Some((flag_type, flag)) = flag.split_once("::") else {
+                return Err(RecordParsingError::BadFlagsFormat());
+            };
+            if flag_type.is_empty() || flag.is_empty() {
+                return Err(RecordParsingError::BadFlagsFormat());
+            }
+            if flags_type.is_some_and(|flags_type| flags_type != flag_type) {
+                return Err(RecordParsingError::BadFlagsFormat());
+            }
+            flags_type = Some(flag_type);
+            if !flags.iter().any(|known_flag| known_flag == flag) {
+                flags.push(flag.to_string());
+            }
+        }
+        Ok(FlagsRecordArg {
+            name,
+            flags_type: flags_type.unwrap().to_string(),
+            flags,
+        })
+    }
+}
+
+/// Structure value written `{src: x32(0x1000) dst: x32(0x2000)}`, its type being named after the
+/// argument unless written before the braces (eg. `DmaDesc{...}`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructRecordArg {
+    pub(crate) name: String,
+    pub(crate) struct_type: String,
+    pub(crate) fields: Vec<UniRecordArgVariant>,
+}
+
+/// Array of structure values of the same type, written `[{...},{...}]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructArrayRecordArg {
+    pub(crate) name: String,
+    pub(crate) struct_type: String,
+    pub(crate) elements: Vec<Vec<UniRecordArgVariant>>,
+}
+
+impl StructRecordArg {
+    pub fn dissassemble(self) -> (String, String, Vec<UniRecordArgVariant>) {
+        (self.name, self.struct_type, self.fields)
+    }
+
+    /// Type and fields of a structure value, the type defaulting to the argument name
+    fn read_value(
+        name: &str,
+        value: &str,
+    ) -> Result<(String, Vec<UniRecordArgVariant>), RecordParsingError> {
+        let (struct_type, fields) = value
+            .strip_suffix('}')
+            .and_then(|value| value.split_once('{'))
+            .ok_or(RecordParsingError::BadStructFormat())?;
+        let struct_type = match struct_type {
+            "" => pascal_case(name),
+            struct_type => struct_type.to_string(),
+        };
+        let fields: Vec<&str> = split_record_args(fields.trim())
+            .into_iter()
+            .filter(|token| !token.is_empty())
+            .collect();
+        if fields.is_empty() || !fields.len().is_multiple_of(2) {
+            return Err(RecordParsingError::BadStructFormat());
+        }
+        let fields = fields
+            .chunks(2)
+            .map(|field| UniRecordArgVariant::from(field[0], field[1]))
+            .collect::<Result<Vec<UniRecordArgVariant>, RecordParsingError>>()?;
+        Ok((struct_type, fields))
+    }
+
+    fn from(name: String, value: &str) -> Result<StructRecordArg, RecordParsingError> {
+        let (struct_type, fields) = Self::read_value(&name, value)?;
+        Ok(StructRecordArg {
+            name,
+            struct_type,
+            fields,
+        })
+    }
+}
+
+impl StructArrayRecordArg {
+    pub fn dissassemble(self) -> (String, String, Vec<Vec<UniRecordArgVariant>>) {
+        (self.name, self.struct_type, self.elements)
+    }
+
+    fn from(name: String, value: &str) -> Result<StructArrayRecordArg, RecordParsingError> {
+        let elements = value
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'))
+            .ok_or(RecordParsingError::BadStructFormat())?;
+        let mut struct_type = None;
+        let mut element_fields = Vec::new();
+        for element in split_outside_braces(elements, ',') {
+            let element = element.trim();
+            // Trailing comma, as in the other arrays
+            if element.is_empty() {
+                continue;
+            }
+            let (element_type, fields) = StructRecordArg::read_value(&name, element)?;
+            if struct_type.is_some_and(|struct_type| struct_type != element_type) {
+                return Err(RecordParsingError::MixedStructArray);
+            }
+            struct_type = Some(element_type);
+            element_fields.push(fields);
+        }
+        // Every element is an instance of the same structure definition
+        let field_types = |fields: &Vec<UniRecordArgVariant>| -> Vec<(String, MemberType)> {
+            fields
+                .iter()
+                .map(|field| (field.get_name().clone(), field.get_type()))
+                .collect()
+        };
+        let Some(first_element) = element_fields.first() else {
+            return Err(RecordParsingError::BadStructFormat());
+        };
+        let first_field_types = field_types(first_element);
+        if element_fields
+            .iter()
+            .any(|fields| field_types(fields) != first_field_types)
+        {
+            return Err(RecordParsingError::MixedStructArray);
+        }
+        Ok(StructArrayRecordArg {
+            name,
+            struct_type: struct_type.unwrap(),
+            elements: element_fields,
+        })
+    }
+}
+
+/// Split the text on the separator, except inside braces
+fn split_outside_braces(text: &str, separator: char) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth: usize = 0;
+    let mut token_start = 0;
+    for (index, character) in text.char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            character if character == separator && depth == 0 => {
+                tokens.push(&text[token_start..index]);
+                token_start = index + character.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    tokens.push(&text[token_start..]);
+    tokens
+}
+
+/// Split the arguments of a record on spaces, the structure values written between braces
+/// being kept whole
+pub(crate) fn split_record_args(line: &str) -> Vec<&str> {
+    split_outside_braces(line, ' ')
+}
+
+/// Split an enum member written `value` or `value=discriminant`, the discriminant being an
+/// integer literal
+pub(crate) fn parse_enum_member(member: &str) -> Result<(String, Option<u32>), RecordParsingError> {
+    let Some((value, discriminant)) = member.split_once('=') else {
+        return Ok((member.to_string(), None));
+    };
+    let discriminant =
+        parse_integer(discriminant, false).map_err(RecordParsingError::BadIdDiscriminant)?;
+    Ok((value.to_string(), Some(discriminant)))
+}
+
+impl IdentifierRecordArg {
+    pub fn dissassemble(self) -> (String, String, String) {
+        (self.name, self.enum_type, self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniRecordArg<T> {
+    pub(crate) name: String,
+    pub(crate) value: T,
+}
+impl<T> UniRecordArg<T> {
+    pub fn dissassemble(self) -> (String, T) {
+        (self.name, self.value)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RecordParsingError {
+    BadX8Format(LiteralError),
+    BadX16Format(LiteralError),
+    BadX32Format(LiteralError),
+    BadX64Format(LiteralError),
+    BadX128Format(LiteralError),
+    BadU8Format(LiteralError),
+    BadU16Format(LiteralError),
+    BadU32Format(LiteralError),
+    BadU64Format(LiteralError),
+    BadU128Format(LiteralError),
+    BadI8Format(LiteralError),
+    BadI16Format(LiteralError),
+    BadI32Format(LiteralError),
+    BadI64Format(LiteralError),
+    BadI128Format(LiteralError),
+    BadF32Format(ParseFloatError),
+    BadF64Format(ParseFloatError),
+    BadX8ArrayFieldSyntax(LiteralError, usize),
+    BadX16ArrayFieldSyntax(LiteralError, usize),
+    BadX32ArrayFieldSyntax(LiteralError, usize),
+    BadX64ArrayFieldSyntax(LiteralError, usize),
+    BadX128ArrayFieldSyntax(LiteralError, usize),
+    BadU8ArrayFieldSyntax(LiteralError, usize),
+    BadU16ArrayFieldSyntax(LiteralError, usize),
+    BadU32ArrayFieldSyntax(LiteralError, usize),
+    BadU64ArrayFieldSyntax(LiteralError, usize),
+    BadU128ArrayFieldSyntax(LiteralError, usize),
+    BadI8ArrayFieldSyntax(LiteralError, usize),
+    BadI16ArrayFieldSyntax(LiteralError, usize),
+    BadI32ArrayFieldSyntax(LiteralError, usize),
+    BadI64ArrayFieldSyntax(LiteralError, usize),
+    BadI128ArrayFieldSyntax(LiteralError, usize),
+    BadF32ArrayFieldSyntax(ParseFloatError, usize),
+    BadF64ArrayFieldSyntax(ParseFloatError, usize),
+    BadBoolFormat(),
+    BadIdFormat(),
+    BadIdDiscriminant(LiteralError),
+    BadFlagsFormat(),
+    BadStructFormat(),
+    MixedStructArray,
+    MissingExpectedValue,
+    DuplicateExpectationArg(String),
+    BadExpectationMask,
+    UnknownArgType(String),
+    UnexpectedArgType(MemberType),
+}
+
+impl Display for RecordParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadX8Format(e) => write!(f, "Error parsing u8 hexadecimal number: {e}"),
+            Self::BadX16Format(e) => write!(f, "Error parsing u16 hexadecimal number: {e}"),
+            Self::BadX32Format(e) => write!(f, "Error parsing u32 hexadecimal number: {e}"),
+            Self::BadX64Format(e) => write!(f, "Error parsing u64 hexadecimal number: {e}"),
+            Self::BadX128Format(e) => write!(f, "Error parsing u128 hexadecimal number: {e}"),
+            Self::BadU8Format(e) => write!(f, "Error parsing u8 number: {e}"),
+            Self::BadU16Format(e) => write!(f, "Error parsing u16 number: {e}"),
+            Self::BadU32Format(e) => write!(f, "Error parsing u32 number: {e}"),
+            Self::BadU64Format(e) => write!(f, "Error parsing u64 number: {e}"),
+            Self::BadU128Format(e) => write!(f, "Error parsing u128 number: {e}"),
+            Self::BadI8Format(e) => write!(f, "Error parsing i8 number: {e}"),
+            Self::BadI16Format(e) => write!(f, "Error parsing i16 number: {e}"),
+            Self::BadI32Format(e) => write!(f, "Error parsing i32 number: {e}"),
+            Self::BadI64Format(e) => write!(f, "Error parsing i64 number: {e}"),
+            Self::BadI128Format(e) => write!(f, "Error parsing i128 number: {e}"),
+            Self::BadF32Format(e) => write!(f, "Error parsing f32 number: {e}"),
+            Self::BadF64Format(e) => write!(f, "Error parsing f64 number: {e}"),
+            Self::BadX8ArrayFieldSyntax(e, index) => write!(
+                f,
+                "Error parsing u8 hexadecimal number in array at pos {index} : {e}"
+            ),
+            Self::BadX16ArrayFieldSyntax(e, index) => write!(
+                f,
+                "Error parsing u16 hexadecimal number in array at pos {index}: {e}"
+            ),
+            Self::BadX32ArrayFieldSyntax(e, index) => write!(
+                f,
+                "Error parsing u32 hexadecimal number in array at pos {index}: {e}"
+            ),
+            Self::BadX64ArrayFieldSyntax(e, index) => write!(
+                f,
+                "Error parsing u64 hexadecimal number in array at pos {index}: {e}"
+            ),
+            Self::BadX128ArrayFieldSyntax(e, index) => write!(
+                f,
+                "Error parsing u128 hexadecimal number in array at pos {index}: {e}"
+            ),
+            Self::BadU8ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing u8 number in array at pos {index}: {e}")
+            }
+            Self::BadU16ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing u16 number in array at pos {index}: {e}")
+            }
+            Self::BadU32ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing u32 number in array at pos {index}: {e}")
+            }
+            Self::BadU64ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing u64 number in array at pos {index}: {e}")
+            }
+            Self::BadU128ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing u128 number in array at pos {index}: {e}")
+            }
+            Self::BadI8ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing i8 number in array at pos {index}: {e}")
+            }
+            Self::BadI16ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing i16 number in array at pos {index}: {e}")
+            }
+            Self::BadI32ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing i32 number in array at pos {index}: {e}")
+            }
+            Self::BadI64ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing i64 number in array at pos {index}: {e}")
+            }
+            Self::BadI128ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing i128 number in array at pos {index}: {e}")
+            }
+            Self::BadF32ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing f32 number in array at pos {index}: {e}")
+            }
+            Self::BadF64ArrayFieldSyntax(e, index) => {
+                write!(f, "Error parsing f64 number in array at pos {index}: {e}")
+            }
+            Self::BadBoolFormat() => write!(f, "Boolean must be `true`, `false` or an integer"),
+            Self::BadIdFormat() => write!(f, "Identifier must be written `EnumType::value`"),
+            Self::BadIdDiscriminant(e) => write!(f, "Error parsing enum discriminant: {e}"),
+            Self::BadFlagsFormat() => write!(
+                f,
+                "Flags must be written `FlagsType::flag|FlagsType::other_flag` or `FlagsType`"
+            ),
+            Self::BadStructFormat() => write!(
+                f,
+                "Structure must be written `{{field: type(value) ...}}` or `StructType{{...}}`, with at least one field"
+            ),
+            Self::MixedStructArray => write!(
+                f,
+                "Elements of a structure array must have the same type and fields"
+            ),
+            Self::MissingExpectedValue => {
+                write!(f, "Expected-value record has no `expect` argument")
+            }
+            Self::DuplicateExpectationArg(arg_name) => write!(
+                f,
+                "Expected-value record has several `{arg_name}` arguments"
+            ),
+            Self::BadExpectationMask => write!(
+                f,
+                "Expectation mask must be an integer of the same type as the expected value"
+            ),
+            Self::UnknownArgType(arg_type) => write!(f, "Unknown argument type `{arg_type}`"),
+            Self::UnexpectedArgType(arg_type) => {
+                write!(f, "Value of type `{arg_type}` where another type is expected")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordParsingError {}
+
+impl UniRecordArgVariant {
+    pub fn get_type(&self) -> MemberType {
+        match self {
+            UniRecordArgVariant::X8(_) => MemberType::X8,
+            UniRecordArgVariant::X16(_) => MemberType::X16,
+            UniRecordArgVariant::X32(_) => MemberType::X32,
+            UniRecordArgVariant::X64(_) => MemberType::X64,
+            UniRecordArgVariant::X128(_) => MemberType::X128,
+            UniRecordArgVariant::U8(_) => MemberType::U8,
+            UniRecordArgVariant::U16(_) => MemberType::U16,
+            UniRecordArgVariant::U32(_) => MemberType::U32,
+            UniRecordArgVariant::U64(_) => MemberType::U64,
+            UniRecordArgVariant::U128(_) => MemberType::U128,
+            UniRecordArgVariant::I8(_) => MemberType::I8,
+            UniRecordArgVariant::I16(_) => MemberType::I16,
+            UniRecordArgVariant::I32(_) => MemberType::I32,
+            UniRecordArgVariant::I64(_) => MemberType::I64,
+            UniRecordArgVariant::I128(_) => MemberType::I128,
+            UniRecordArgVariant::F32(_) => MemberType::F32,
+            UniRecordArgVariant::F64(_) => MemberType::F64,
+            UniRecordArgVariant::ArrayOfX8(_) => MemberType::ArrayOfX8,
+            UniRecordArgVariant::ArrayOfX16(_) => MemberType::ArrayOfX16,
+            UniRecordArgVariant::ArrayOfX32(_) => MemberType::ArrayOfX32,
+            UniRecordArgVariant::ArrayOfX64(_) => MemberType::ArrayOfX64,
+            UniRecordArgVariant::ArrayOfX128(_) => MemberType::ArrayOfX128,
+            UniRecordArgVariant::ArrayOfU8(_) => MemberType::ArrayOfU8,
+            UniRecordArgVariant::ArrayOfU16(_) => MemberType::ArrayOfU16,
+            UniRecordArgVariant::ArrayOfU32(_) => MemberType::ArrayOfU32,
+            UniRecordArgVariant::ArrayOfU64(_) => MemberType::ArrayOfU64,
+            UniRecordArgVariant::ArrayOfU128(_) => MemberType::ArrayOfU128,
+            UniRecordArgVariant::ArrayOfI8(_) => MemberType::ArrayOfI8,
+            UniRecordArgVariant::ArrayOfI16(_) => MemberType::ArrayOfI16,
+            UniRecordArgVariant::ArrayOfI32(_) => MemberType::ArrayOfI32,
+            UniRecordArgVariant::ArrayOfI64(_) => MemberType::ArrayOfI64,
+            UniRecordArgVariant::ArrayOfI128(_) => MemberType::ArrayOfI128,
+            UniRecordArgVariant::ArrayOfF32(_) => MemberType::ArrayOfF32,
+            UniRecordArgVariant::ArrayOfF64(_) => MemberType::ArrayOfF64,
+            UniRecordArgVariant::Identifier(arg) => MemberType::Identifier(arg.enum_type.clone()),
+            UniRecordArgVariant::Flags(arg) => MemberType::Flags(arg.flags_type.clone()),
+            UniRecordArgVariant::Struct(arg) => MemberType::Struct(arg.struct_type.clone()),
+            UniRecordArgVariant::ArrayOfStruct(arg) => {
+                MemberType::ArrayOfStruct(arg.struct_type.clone())
+            }
+            UniRecordArgVariant::Bool(_) => MemberType::Bool,
+        }
+    }
+
+    /// Value of an unsigned integer argument, `None` for any other argument type
+    pub fn get_unsigned_value(&self) -> Option<u64> {
+        match self {
+            UniRecordArgVariant::X8(arg) | UniRecordArgVariant::U8(arg) => Some(arg.value.into()),
+            UniRecordArgVariant::X16(arg) | UniRecordArgVariant::U16(arg) => Some(arg.value.into()),
+            UniRecordArgVariant::X32(arg) | UniRecordArgVariant::U32(arg) => Some(arg.value.into()),
+            UniRecordArgVariant::X64(arg) | UniRecordArgVariant::U64(arg) => Some(arg.value),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        match self {
+            UniRecordArgVariant::X8(arg) => &arg.name,
+            UniRecordArgVariant::X16(arg) => &arg.name,
+            UniRecordArgVariant::X32(arg) => &arg.name,
+            UniRecordArgVariant::X64(arg) => &arg.name,
+            UniRecordArgVariant::X128(arg) => &arg.name,
+            UniRecordArgVariant::U8(arg) => &arg.name,
+            UniRecordArgVariant::U16(arg) => &arg.name,
+            UniRecordArgVariant::U32(arg) => &arg.name,
+            UniRecordArgVariant::U64(arg) => &arg.name,
+            UniRecordArgVariant::U128(arg) => &arg.name,
+            UniRecordArgVariant::I8(arg) => &arg.name,
+            UniRecordArgVariant::I16(arg) => &arg.name,
+            UniRecordArgVariant::I32(arg) => &arg.name,
+            UniRecordArgVariant::I64(arg) => &arg.name,
+            UniRecordArgVariant::I128(arg) => &arg.name,
+            UniRecordArgVariant::F32(arg) => &arg.name,
+            UniRecordArgVariant::F64(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfX8(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfX16(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfX32(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfX64(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfX128(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfU8(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfU16(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfU32(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfU64(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfU128(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfI8(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfI16(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfI32(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfI64(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfI128(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfF32(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfF64(arg) => &arg.name,
+            UniRecordArgVariant::Identifier(arg) => &arg.name,
+            UniRecordArgVariant::Flags(arg) => &arg.name,
+            UniRecordArgVariant::Struct(arg) => &arg.name,
+            UniRecordArgVariant::ArrayOfStruct(arg) => &arg.name,
+            UniRecordArgVariant::Bool(arg) => &arg.name,
+        }
+    }
+
+    pub fn from(
+        record_arg_key: &str,
+        record_arg_value: &str,
+    ) -> Result<UniRecordArgVariant, RecordParsingError> {
+        if record_arg_value.contains('{') {
+            let record_arg_name = String::from(record_arg_key.split(':').next().unwrap());
+            return if record_arg_value.starts_with('[') {
+                Ok(UniRecordArgVariant::ArrayOfStruct(
+                    StructArrayRecordArg::from(record_arg_name, record_arg_value)?,
+                ))
+            } else {
+                Ok(UniRecordArgVariant::Struct(StructRecordArg::from(
+                    record_arg_name,
+                    record_arg_value,
+                )?))
+            };
+        }
+        let arg_value_split = record_arg_value.split(&['(', ')']).collect::<Vec<&str>>();
+        let arg_type = arg_value_split[0];
+        let Some(arg_value_content) = arg_value_split.get(1) else {
+            return Err(RecordParsingError::UnknownArgType(arg_type.to_string()));
+        };
+
+        let record_arg_name = String::from(record_arg_key.split(':').next().unwrap());
+        let value_field: Vec<&str> = arg_value_content.split(&['[', ']']).collect();
+
+        if value_field.len() > 1 {
+            let value_list: Vec<&str> = value_field[1].split(',').collect();
+            match arg_type {
+                "x8" => Ok(UniRecordArgVariant::ArrayOfX8(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(index, v)| {
+                            parse_integer(v, true)
+                                .map_err(|e| RecordParsingError::BadX8ArrayFieldSyntax(e, index))
+                        })
+                        .collect::<Result<Vec<u8>, RecordParsingError>>()?,
+                })),
+
+                "x16" => Ok(UniRecordArgVariant::ArrayOfX16(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(index, v)| {
+                            parse_integer(v, true)
+                                .map_err(|e| RecordParsingError::BadX16ArrayFieldSyntax(e, index))
+                        })
+                        .collect::<Result<Vec<u16>, RecordParsingError>>()?,
+                })),
+
+                "x32" => Ok(UniRecordArgVariant::ArrayOfX32(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(index, v)| {
+                            parse_integer(v, true)
+                                .map_err(|e| RecordParsingError::BadX32ArrayFieldSyntax(e, index))
+                        })
+                        .collect::<Result<Vec<u32>, RecordParsingError>>()?,
+                })),
+
+                "x64" => Ok(UniRecordArgVariant::ArrayOfX64(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(index, v)| {
+                            parse_integer(v, true)
+                                .map_err(|e| RecordParsingError::BadX64ArrayFieldSyntax(e, index))
+                        })
+                        .collect::<Result<Vec<u64>, RecordParsingError>>()?,
+                })),
+                "x128" => Ok(UniRecordArgVariant::ArrayOfX128(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(index, v)| {
+                            parse_integer(v, true)
+                                .map_err(|e| RecordParsingError::BadX128ArrayFieldSyntax(e, index))
+                        })
+                        .collect::<Result<Vec<u128>, RecordParsingError>>()?,
+                })),
+
+                "u8" => Ok(UniRecordArgVariant::ArrayOfU8(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadU8ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<u8>, RecordParsingError>>()?,
+                })),
+                "i8" => Ok(UniRecordArgVariant::ArrayOfI8(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadI8ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<i8>, RecordParsingError>>()?,
+                })),
+                "u16" => Ok(UniRecordArgVariant::ArrayOfU16(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadU16ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<u16>, RecordParsingError>>()?,
+                })),
+                "i16" => Ok(UniRecordArgVariant::ArrayOfI16(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadI16ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<i16>, RecordParsingError>>()?,
+                })),
+                "u32" => Ok(UniRecordArgVariant::ArrayOfU32(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadU32ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<u32>, RecordParsingError>>()?,
+                })),
+                "i32" => Ok(UniRecordArgVariant::ArrayOfI32(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadI32ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<i32>, RecordParsingError>>()?,
+                })),
+                "u64" => Ok(UniRecordArgVariant::ArrayOfU64(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadU64ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<u64>, RecordParsingError>>()?,
+                })),
+                "u128" => Ok(UniRecordArgVariant::ArrayOfU128(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadU128ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<u128>, RecordParsingError>>()?,
+                })),
+                "i64" => Ok(UniRecordArgVariant::ArrayOfI64(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadI64ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<i64>, RecordParsingError>>()?,
+                })),
+                "i128" => Ok(UniRecordArgVariant::ArrayOfI128(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            parse_integer(v, false)
+                                .map_err(|e| RecordParsingError::BadI128ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<i128>, RecordParsingError>>()?,
+                })),
+                "f32" => Ok(UniRecordArgVariant::ArrayOfF32(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            v.parse()
+                                .map_err(|e| RecordParsingError::BadF32ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<f32>, RecordParsingError>>()?,
+                })),
+                "f64" => Ok(UniRecordArgVariant::ArrayOfF64(UniRecordArg {
+                    name: record_arg_name,
+                    value: value_list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            v.parse()
+                                .map_err(|e| RecordParsingError::BadF64ArrayFieldSyntax(e, i))
+                        })
+                        .collect::<Result<Vec<f64>, RecordParsingError>>()?,
+                })),
+                &_ => Err(RecordParsingError::UnknownArgType(format!("{arg_type}[]"))),
+            }
+        } else {
+            let value = value_field[0];
+            match arg_type {
+                "id" => {
+                    let tokens: Vec<&str> = value.split("::").collect();
+                    if tokens.len() != 2 {
+                        return Err(RecordParsingError::BadIdFormat());
+                    }
+
+                    let (value, discriminant) = parse_enum_member(tokens[1])?;
+
+                    Ok(UniRecordArgVariant::Identifier(IdentifierRecordArg {
+                        name: record_arg_name,
+                        enum_type: tokens[0].to_string(),
+                        value,
+                        discriminant,
+                    }))
+                }
+                "flags" => Ok(UniRecordArgVariant::Flags(FlagsRecordArg::from(
+                    record_arg_name,
+                    value,
+                )?)),
+                "x8" => {
+                    let value =
+                        parse_integer(value, true).map_err(RecordParsingError::BadX8Format)?;
+
+                    Ok(UniRecordArgVariant::X8(UniRecordArg {
+                        name: record_arg_name,
+                        value,
+                    }))
+                }
+                "x16" => {
+                    let value =
+                        parse_integer(value, true).map_err(RecordParsingError::BadX16Format)?;
+
+                    Ok(UniRecordArgVariant::X16(UniRecordArg {
+                        name: record_arg_name,
+                        value,
+                    }))
+                }
+                "x32" => {
+                    let value =
+                        parse_integer(value, true).map_err(RecordParsingError::BadX32Format)?;
+
+                    Ok(UniRecordArgVariant::X32(UniRecordArg {
+                        name: record_arg_name,
+                        value,
+                    }))
+                }
+                "x64" => {
+                    let value =
+                        parse_integer(value, true).map_err(RecordParsingError::BadX64Format)?;
+
+                    Ok(UniRecordArgVariant::X64(UniRecordArg {
+                        name: record_arg_name,
+                        value,
+                    }))
+                }
+                "x128" => {
+                    let value =
+                        parse_integer(value, true).map_err(RecordParsingError::BadX128Format)?;
+
+                    Ok(UniRecordArgVariant::X128(UniRecordArg {
+                        name: record_arg_name,
+                        value,
+                    }))
+                }
+
+                "u8" => Ok(UniRecordArgVariant::U8(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadU8Format)?,
+                })),
+                "i8" => Ok(UniRecordArgVariant::I8(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadI8Format)?,
+                })),
+                "u16" => Ok(UniRecordArgVariant::U16(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadU16Format)?,
+                })),
+                "i16" => Ok(UniRecordArgVariant::I16(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadI16Format)?,
+                })),
+                "u32" => Ok(UniRecordArgVariant::U32(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadU32Format)?,
+                })),
+                "i32" => Ok(UniRecordArgVariant::I32(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadI32Format)?,
+                })),
+                "u64" => Ok(UniRecordArgVariant::U64(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadU64Format)?,
+                })),
+                "u128" => Ok(UniRecordArgVariant::U128(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false)
+                        .map_err(RecordParsingError::BadU128Format)?,
+                })),
+                "i64" => Ok(UniRecordArgVariant::I64(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false).map_err(RecordParsingError::BadI64Format)?,
+                })),
+                "i128" => Ok(UniRecordArgVariant::I128(UniRecordArg {
+                    name: record_arg_name,
+                    value: parse_integer(value, false)
+                        .map_err(RecordParsingError::BadI128Format)?,
+                })),
+                "f32" => Ok(UniRecordArgVariant::F32(UniRecordArg {
+                    name: record_arg_name,
+                    value: value.parse().map_err(RecordParsingError::BadF32Format)?,
+                })),
+                "f64" => Ok(UniRecordArgVariant::F64(UniRecordArg {
+                    name: record_arg_name,
+                    value: value.parse().map_err(RecordParsingError::BadF64Format)?,
+                })),
+                "bool" => {
+                    let bool_value;
+                    if let Ok(value) = value.parse::<bool>() {
+                        bool_value = value;
+                    } else {
+                        let value = parse_integer::<u128>(value, false)
+                            .map_err(|_| RecordParsingError::BadBoolFormat())?;
+                        bool_value = value != 0;
+                    }
+                    Ok(UniRecordArgVariant::Bool(UniRecordArg {
+                        name: record_arg_name,
+                        value: bool_value,
+                    }))
+                }
+
+                &_ => Err(RecordParsingError::UnknownArgType(arg_type.to_string())),
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UniRecord {
+    name: String,
+    args: Vec<UniRecordArgVariant>,
+}
+
+impl UniRecord {
+    pub fn new(name: String, args: Vec<UniRecordArgVariant>) -> UniRecord {
+        UniRecord { name, args }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_args(&self) -> &[UniRecordArgVariant] {
+        &self.args
+    }
+
+    pub fn dissassemble(self) -> (String, Vec<UniRecordArgVariant>) {
+        (self.name, self.args)
+    }
+
+    /// Turn a record read from an expected-value record (`#? ... ?#`) into the record of its
+    /// read-back operation. The `expect` and `mask` arguments are moved at the end of the
+    /// argument list and a full mask is added when the expected value is an integer without one.
+    pub fn into_expectation(self) -> Result<UniRecord, RecordParsingError> {
+        let (name, args) = self.dissassemble();
+        let mut expect = None;
+        let mut mask = None;
+        let mut command_args = Vec::new();
+        for arg in args {
+            let expectation_arg = match arg.get_name().as_str() {
+                EXPECT_ARG_NAME => &mut expect,
+                MASK_ARG_NAME => &mut mask,
+                _ => {
+                    command_args.push(arg);
+                    continue;
+                }
+            };
+            if expectation_arg.is_some() {
+                return Err(RecordParsingError::DuplicateExpectationArg(
+                    arg.get_name().clone(),
+                ));
+            }
+            *expectation_arg = Some(arg);
+        }
+
+        let expect = expect.ok_or(RecordParsingError::MissingExpectedValue)?;
+        let full_mask = expect.full_mask();
+        let mask = match (mask, full_mask) {
+            (Some(mask), Some(_)) if mask.get_type() == expect.get_type() => Some(mask),
+            (None, full_mask) => full_mask,
+            _ => return Err(RecordParsingError::BadExpectationMask),
+        };
+
+        command_args.push(expect);
+        command_args.extend(mask);
+        Ok(UniRecord::new(name + EXPECT_RECORD_SUFFIX, command_args))
+    }
+}
+
+/// Name of the argument holding the expected value in an expected-value record
+pub const EXPECT_ARG_NAME: &str = "expect";
+/// Name of the argument holding the mask applied to the expected value
+pub const MASK_ARG_NAME: &str = "mask";
+/// Suffix appended to the record name of an expected-value record
+pub const EXPECT_RECORD_SUFFIX: &str = "Expect";
+
+impl UniRecordArgVariant {
+    /// Mask with every bit set named after the expected-value mask argument.
+    /// Only integer scalars can be masked.
+    fn full_mask(&self) -> Option<UniRecordArgVariant> {
+        let name = String::from(MASK_ARG_NAME);
+        let mask = match self {
+            UniRecordArgVariant::X8(_) => UniRecordArgVariant::X8(UniRecordArg {
+                name,
+                value: u8::MAX,
+            }),
+            UniRecordArgVariant::X16(_) => UniRecordArgVariant::X16(UniRecordArg {
+                name,
+                value: u16::MAX,
+            }),
+            UniRecordArgVariant::X32(_) => UniRecordArgVariant::X32(UniRecordArg {
+                name,
+                value: u32::MAX,
+            }),
+            UniRecordArgVariant::X64(_) => UniRecordArgVariant::X64(UniRecordArg {
+                name,
+                value: u64::MAX,
+            }),
+            UniRecordArgVariant::X128(_) => UniRecordArgVariant::X128(UniRecordArg {
+                name,
+                value: u128::MAX,
+            }),
+            UniRecordArgVariant::U8(_) => UniRecordArgVariant::U8(UniRecordArg {
+                name,
+                value: u8::MAX,
+            }),
+            UniRecordArgVariant::U16(_) => UniRecordArgVariant::U16(UniRecordArg {
+                name,
+                value: u16::MAX,
+            }),
+            UniRecordArgVariant::U32(_) => UniRecordArgVariant::U32(UniRecordArg {
+                name,
+                value: u32::MAX,
+            }),
+            UniRecordArgVariant::U64(_) => UniRecordArgVariant::U64(UniRecordArg {
+                name,
+                value: u64::MAX,
+            }),
+            UniRecordArgVariant::U128(_) => UniRecordArgVariant::U128(UniRecordArg {
+                name,
+                value: u128::MAX,
+            }),
+            UniRecordArgVariant::I8(_) => UniRecordArgVariant::I8(UniRecordArg { name, value: -1 }),
+            UniRecordArgVariant::I16(_) => {
+                UniRecordArgVariant::I16(UniRecordArg { name, value: -1 })
+            }
+            UniRecordArgVariant::I32(_) => {
+                UniRecordArgVariant::I32(UniRecordArg { name, value: -1 })
+            }
+            UniRecordArgVariant::I64(_) => {
+                UniRecordArgVariant::I64(UniRecordArg { name, value: -1 })
+            }
+            UniRecordArgVariant::I128(_) => {
+                UniRecordArgVariant::I128(UniRecordArg { name, value: -1 })
+            }
+            _ => return None,
+        };
+        Some(mask)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_arg_parsing_test() {
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x8(0x25)"),
+            Ok(UniRecordArgVariant::X8(UniRecordArg {
+                name: String::from("test_val"),
+                value: 0x25
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x16(0xFF3A)"),
+            Ok(UniRecordArgVariant::X16(UniRecordArg {
+                name: String::from("test_val"),
+                value: 0xFF3A
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x32(0x23A4F89E)"),
+            Ok(UniRecordArgVariant::X32(UniRecordArg {
+                name: String::from("test_val"),
+                value: 0x23A4F89E
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x64(0xEE3FB4FAD554CE05)"),
+            Ok(UniRecordArgVariant::X64(UniRecordArg {
+                name: String::from("test_val"),
+                value: 0xEE3FB4FAD554CE05
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x128(0x1EE3FB4FAD554CE05DA5708C1)"),
+            Ok(UniRecordArgVariant::X128(UniRecordArg {
+                name: String::from("test_val"),
+                value: 0x1EE3FB4FAD554CE05DA5708C1
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from(
+                "test_val:",
+                "i128([-1,170141183460469231731687303715884105727])"
+            ),
+            Ok(UniRecordArgVariant::ArrayOfI128(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![-1, i128::MAX]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u8(250)"),
+            Ok(UniRecordArgVariant::U8(UniRecordArg {
+                name: String::from("test_val"),
+                value: 250
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u16(25690)"),
+            Ok(UniRecordArgVariant::U16(UniRecordArg {
+                name: String::from("test_val"),
+                value: 25690
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u32(589214450)"),
+            Ok(UniRecordArgVariant::U32(UniRecordArg {
+                name: String::from("test_val"),
+                value: 589214450
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u64(56228558925412450)"),
+            Ok(UniRecordArgVariant::U64(UniRecordArg {
+                name: String::from("test_val"),
+                value: 56228558925412450
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "i8(-105)"),
+            Ok(UniRecordArgVariant::I8(UniRecordArg {
+                name: String::from("test_val"),
+                value: -105
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "i16(-5240)"),
+            Ok(UniRecordArgVariant::I16(UniRecordArg {
+                name: String::from("test_val"),
+                value: -5240
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "i32(-405251740)"),
+            Ok(UniRecordArgVariant::I32(UniRecordArg {
+                name: String::from("test_val"),
+                value: -405251740
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "i64(-85405652565187840)"),
+            Ok(UniRecordArgVariant::I64(UniRecordArg {
+                name: String::from("test_val"),
+                value: -85405652565187840
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "f32(-40525.1740)"),
+            Ok(UniRecordArgVariant::F32(UniRecordArg {
+                name: String::from("test_val"),
+                value: -40_525.176
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test-val:", "f64(-8540565256.5187840)"),
+            Ok(UniRecordArgVariant::F64(UniRecordArg {
+                name: String::from("test-val"),
+                value: -8_540_565_256.518_784
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x8([0x25])"),
+            Ok(UniRecordArgVariant::ArrayOfX8(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![0x25]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x8([0x2A, 0x6F])"),
+            Ok(UniRecordArgVariant::ArrayOfX8(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![0x2A, 0x6F]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u8([88])"),
+            Ok(UniRecordArgVariant::ArrayOfU8(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![88]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u8([88,65])"),
+            Ok(UniRecordArgVariant::ArrayOfU8(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![88, 65]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "f32([52.6])"),
+            Ok(UniRecordArgVariant::ArrayOfF32(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![52.6]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "f32([52.6,-42.4])"),
+            Ok(UniRecordArgVariant::ArrayOfF32(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![52.6, -42.4]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "bool(false)"),
+            Ok(UniRecordArgVariant::Bool(UniRecordArg {
+                name: String::from("test_val"),
+                value: false
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "i16([-0x10,0b11,1_000])"),
+            Ok(UniRecordArgVariant::ArrayOfI16(UniRecordArg {
+                name: String::from("test_val"),
+                value: vec![-0x10, 0b11, 1_000]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "x8([0x25,25])"),
+            Err(RecordParsingError::BadX8ArrayFieldSyntax(
+                LiteralError::MissingHexPrefix,
+                1
+            ))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "u32(1e-3)"),
+            Err(RecordParsingError::BadU32Format(
+                LiteralError::FloatInIntegerContext
+            ))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "bool(52)"),
+            Ok(UniRecordArgVariant::Bool(UniRecordArg {
+                name: String::from("test_val"),
+                value: true
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "bool(0)"),
+            Ok(UniRecordArgVariant::Bool(UniRecordArg {
+                name: String::from("test_val"),
+                value: false
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "id(EnumType::Hello)"),
+            Ok(UniRecordArgVariant::Identifier(IdentifierRecordArg {
+                name: String::from("test_val"),
+                enum_type: String::from("EnumType"),
+                value: String::from("Hello"),
+                discriminant: None
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "flags(CtrlBits::EN|CtrlBits::IRQ)"),
+            Ok(UniRecordArgVariant::Flags(FlagsRecordArg {
+                name: String::from("test_val"),
+                flags_type: String::from("CtrlBits"),
+                flags: vec![String::from("EN"), String::from("IRQ")]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "flags(CtrlBits::EN|Other::IRQ)"),
+            Err(RecordParsingError::BadFlagsFormat())
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "flags()"),
+            Err(RecordParsingError::BadFlagsFormat())
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "flags(CtrlBits::EN|CtrlBits::)"),
+            Err(RecordParsingError::BadFlagsFormat())
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "flags(::EN)"),
+            Err(RecordParsingError::BadFlagsFormat())
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("test_val:", "id(EnumType::Hello=0x3)"),
+            Ok(UniRecordArgVariant::Identifier(IdentifierRecordArg {
+                name: String::from("test_val"),
+                enum_type: String::from("EnumType"),
+                value: String::from("Hello"),
+                discriminant: Some(3)
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("desc_info:", "{src: x32(0x10) len: u16(4)}"),
+            Ok(UniRecordArgVariant::Struct(StructRecordArg {
+                name: String::from("desc_info"),
+                struct_type: String::from("DescInfo"),
+                fields: vec![
+                    UniRecordArgVariant::X32(UniRecordArg {
+                        name: String::from("src"),
+                        value: 0x10
+                    }),
+                    UniRecordArgVariant::U16(UniRecordArg {
+                        name: String::from("len"),
+                        value: 4
+                    })
+                ]
+            }))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("descs:", "[Desc{len: u16(4)},Desc{len: u16(8)},]")
+                .map(|arg| arg.get_type()),
+            Ok(MemberType::ArrayOfStruct(String::from("Desc")))
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("descs:", "[Desc{len: u16(4)},Desc{len: x16(0x8)}]"),
+            Err(RecordParsingError::MixedStructArray)
+        );
+        assert_eq!(
+            UniRecordArgVariant::from("desc:", "Desc{len:}"),
+            Err(RecordParsingError::BadStructFormat())
+        );
+    }
+
+    #[test]
+    fn expectation_record_test() {
+        let record = UniRecord::new(
+            String::from("Cmd52"),
+            vec![
+                UniRecordArgVariant::from("expect:", "x8(0x80)").unwrap(),
+                UniRecordArgVariant::from("add:", "x32(0x1043)").unwrap(),
+            ],
+        );
+        assert_eq!(
+            record.into_expectation(),
+            Ok(UniRecord::new(
+                String::from("Cmd52Expect"),
+                vec![
+                    UniRecordArgVariant::from("add:", "x32(0x1043)").unwrap(),
+                    UniRecordArgVariant::from("expect:", "x8(0x80)").unwrap(),
+                    UniRecordArgVariant::from("mask:", "x8(0xff)").unwrap(),
+                ]
+            ))
+        );
+
+        let record = UniRecord::new(
+            String::from("Cmd52"),
+            vec![
+                UniRecordArgVariant::from("expect:", "x8(0x80)").unwrap(),
+                UniRecordArgVariant::from("mask:", "u16(12)").unwrap(),
+            ],
+        );
+        assert_eq!(
+            record.into_expectation(),
+            Err(RecordParsingError::BadExpectationMask)
+        );
+
+        let record = UniRecord::new(
+            String::from("Cmd52"),
+            vec![UniRecordArgVariant::from("add:", "x32(0x1043)").unwrap()],
+        );
+        assert_eq!(
+            record.into_expectation(),
+            Err(RecordParsingError::MissingExpectedValue)
+        );
+
+        let record = UniRecord::new(
+            String::from("Cmd52"),
+            vec![
+                UniRecordArgVariant::from("expect:", "x8(0x80)").unwrap(),
+                UniRecordArgVariant::from("mask:", "x8(0xf0)").unwrap(),
+                UniRecordArgVariant::from("mask:", "x8(0x0f)").unwrap(),
+            ],
+        );
+        assert_eq!(
+            record.into_expectation(),
+            Err(RecordParsingError::DuplicateExpectationArg(String::from(
+                "mask"
+            )))
+        );
+    }
+}
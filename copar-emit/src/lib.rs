@@ -0,0 +1,345 @@
+//! # Copar emit
+//! Formatting of CoPar records from instrumented Rust code, eg. the low-level access layer of a
+//! driver. The records are written to any [`core::fmt::Write`] sink, one record per line, in the
+//! format expected by the copar parser. The crate is `no_std` and does not allocate.
+//!
+//! ```
+//! use copar_emit::{copar_record, hex32, scope, scope_record};
+//!
+//! # fn main() -> core::fmt::Result {
+//! let mut log = String::new();
+//! copar_record!(log, Cmd52, write = true, add = hex32(0x1043))?;
+//! let mut read_efuse = scope!(log, ReadEfuse)?;
+//! scope_record!(read_efuse, offset = 0x5eau16)?;
+//! read_efuse.end()?;
+//! assert_eq!(
+//!     log,
+//!     "#= Cmd52 write: bool(true) add: x32(0x1043) =#\n\
+//!      #[ ReadEfuse [#\n\
+//!      #- ReadEfuse offset: u16(1514) -#\n\
+//!      #] ReadEfuse ]#\n"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+#![no_std]
+
+use core::fmt::{self, Write};
+use core::ops::{Deref, DerefMut};
+
+/// Value of a record argument, written with its type (eg. `u8(1)`)
+pub trait RecordArg {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result;
+}
+
+/// Value that can be an argument or an element of an array argument
+pub trait ScalarArg {
+    /// Type of the argument in the record (eg. `x32`)
+    const TYPE_NAME: &'static str;
+    /// Write the value without its type
+    fn write_value(&self, sink: &mut dyn Write) -> fmt::Result;
+}
+
+fn write_scalar_arg<T: ScalarArg>(value: &T, sink: &mut dyn Write) -> fmt::Result {
+    write!(sink, "{}(", T::TYPE_NAME)?;
+    value.write_value(sink)?;
+    sink.write_char(')')
+}
+
+impl<T: ScalarArg> RecordArg for [T] {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        write!(sink, "{}([", T::TYPE_NAME)?;
+        for (index, value) in self.iter().enumerate() {
+            // The parser splits the arguments on spaces
+            if index > 0 {
+                sink.write_char(',')?;
+            }
+            value.write_value(sink)?;
+        }
+        sink.write_str("])")
+    }
+}
+
+impl<T: ScalarArg, const N: usize> RecordArg for [T; N] {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        self.as_slice().write_arg(sink)
+    }
+}
+
+impl<T: RecordArg + ?Sized> RecordArg for &T {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        (**self).write_arg(sink)
+    }
+}
+
+macro_rules! impl_display_scalar_arg {
+    ($($value_type:ty => $type_name:literal),*) => {
+        $(
+            impl ScalarArg for $value_type {
+                const TYPE_NAME: &'static str = $type_name;
+                fn write_value(&self, sink: &mut dyn Write) -> fmt::Result {
+                    write!(sink, "{}", self)
+                }
+            }
+
+            impl RecordArg for $value_type {
+                fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+                    write_scalar_arg(self, sink)
+                }
+            }
+        )*
+    };
+}
+
+impl_display_scalar_arg!(
+    u8 => "u8", u16 => "u16", u32 => "u32", u64 => "u64", u128 => "u128",
+    i8 => "i8", i16 => "i16", i32 => "i32", i64 => "i64", i128 => "i128",
+    f32 => "f32", f64 => "f64"
+);
+
+impl RecordArg for bool {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        write!(sink, "bool({self})")
+    }
+}
+
+macro_rules! define_hex_arg {
+    ($($hex_type:ident, $constructor:ident, $value_type:ty => $type_name:literal);*) => {
+        $(
+            #[doc = concat!("Integer written in hexadecimal, as a `", $type_name, "` argument")]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $hex_type(pub $value_type);
+
+            #[doc = concat!("Write the value as a `", $type_name, "` argument")]
+            pub fn $constructor(value: $value_type) -> $hex_type {
+                $hex_type(value)
+            }
+
+            impl ScalarArg for $hex_type {
+                const TYPE_NAME: &'static str = $type_name;
+                fn write_value(&self, sink: &mut dyn Write) -> fmt::Result {
+                    write!(sink, "{:#x}", self.0)
+                }
+            }
+
+            impl RecordArg for $hex_type {
+                fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+                    write_scalar_arg(self, sink)
+                }
+            }
+        )*
+    };
+}
+
+define_hex_arg!(
+    Hex8, hex8, u8 => "x8";
+    Hex16, hex16, u16 => "x16";
+    Hex32, hex32, u32 => "x32";
+    Hex64, hex64, u64 => "x64";
+    Hex128, hex128, u128 => "x128"
+);
+
+/// Enum value argument (eg. `id(EfuseAccess::DDV)`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Id<'a> {
+    pub enum_type: &'a str,
+    pub value: &'a str,
+}
+
+/// Write the value as an `id(enum_type::value)` argument
+pub fn id<'a>(enum_type: &'a str, value: &'a str) -> Id<'a> {
+    Id { enum_type, value }
+}
+
+impl RecordArg for Id<'_> {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        write!(sink, "id({}::{})", self.enum_type, self.value)
+    }
+}
+
+/// Flags argument (eg. `flags(CtrlBits::EN|CtrlBits::IRQ)`), listing the names of the set flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flags<'a> {
+    pub flags_type: &'a str,
+    pub flags: &'a [&'a str],
+}
+
+/// Write the flags as a `flags(flags_type::flag|...)` argument, `flags(flags_type)` when empty
+pub fn flags<'a>(flags_type: &'a str, flags: &'a [&'a str]) -> Flags<'a> {
+    Flags { flags_type, flags }
+}
+
+impl RecordArg for Flags<'_> {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        write!(sink, "flags(")?;
+        if self.flags.is_empty() {
+            write!(sink, "{}", self.flags_type)?;
+        }
+        for (index, flag) in self.flags.iter().enumerate() {
+            if index > 0 {
+                write!(sink, "|")?;
+            }
+            write!(sink, "{}::{flag}", self.flags_type)?;
+        }
+        write!(sink, ")")
+    }
+}
+
+/// Nested structure argument (eg. `Desc{src: x32(0x1000) len: u16(64)}`)
+#[derive(Clone, Copy)]
+pub struct Struct<'a> {
+    pub struct_type: &'a str,
+    pub fields: &'a [(&'a str, &'a dyn RecordArg)],
+}
+
+/// Write the fields as a `struct_type{name: type(value) ...}` argument
+pub fn structure<'a>(
+    struct_type: &'a str,
+    fields: &'a [(&'a str, &'a dyn RecordArg)],
+) -> Struct<'a> {
+    Struct {
+        struct_type,
+        fields,
+    }
+}
+
+impl RecordArg for Struct<'_> {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        write!(sink, "{}{{", self.struct_type)?;
+        for (index, (field_name, field)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                sink.write_char(' ')?;
+            }
+            write!(sink, "{field_name}: ")?;
+            field.write_arg(sink)?;
+        }
+        sink.write_char('}')
+    }
+}
+
+/// Array of nested structures argument, the structures being separated by commas
+#[derive(Clone, Copy)]
+pub struct Structs<'a> {
+    pub elements: &'a [Struct<'a>],
+}
+
+/// Write the structures as a `[struct_type{...},struct_type{...}]` argument
+pub fn structures<'a>(elements: &'a [Struct<'a>]) -> Structs<'a> {
+    Structs { elements }
+}
+
+impl RecordArg for Structs<'_> {
+    fn write_arg(&self, sink: &mut dyn Write) -> fmt::Result {
+        sink.write_char('[')?;
+        for (index, element) in self.elements.iter().enumerate() {
+            if index > 0 {
+                sink.write_char(',')?;
+            }
+            element.write_arg(sink)?;
+        }
+        sink.write_char(']')
+    }
+}
+
+/// Write a record delimited by `#{delimiter} ... {delimiter}#`. Used by the macros.
+#[doc(hidden)]
+pub fn write_record<W: Write>(
+    sink: &mut W,
+    delimiter: char,
+    record_name: &str,
+    args: &[(&str, &dyn RecordArg)],
+) -> fmt::Result {
+    write!(sink, "#{delimiter} {record_name}")?;
+    for (arg_name, arg) in args.iter() {
+        write!(sink, " {arg_name}: ")?;
+        arg.write_arg(sink)?;
+    }
+    writeln!(sink, " {delimiter}#")
+}
+
+/// Scope command records being written. The end record `#] NAME ]#` is written by
+/// [`Scope::end`], or when the scope is dropped. The sink of the scope can still be used
+/// through the scope while it is open.
+pub struct Scope<'a, W: Write> {
+    sink: &'a mut W,
+    record_name: &'static str,
+    is_ended: bool,
+}
+
+impl<'a, W: Write> Scope<'a, W> {
+    /// Write the begin record of the scope. Used by [`scope!`].
+    pub fn begin(sink: &'a mut W, record_name: &'static str) -> Result<Scope<'a, W>, fmt::Error> {
+        writeln!(sink, "#[ {record_name} [#")?;
+        Ok(Scope {
+            sink,
+            record_name,
+            is_ended: false,
+        })
+    }
+
+    /// Write a record giving arguments of the scope command. Used by [`scope_record!`].
+    pub fn record(&mut self, args: &[(&str, &dyn RecordArg)]) -> fmt::Result {
+        write_record(self.sink, '-', self.record_name, args)
+    }
+
+    /// Write the end record of the scope
+    pub fn end(mut self) -> fmt::Result {
+        self.is_ended = true;
+        writeln!(self.sink, "#] {} ]#", self.record_name)
+    }
+}
+
+impl<W: Write> Drop for Scope<'_, W> {
+    fn drop(&mut self) {
+        if !self.is_ended {
+            let _ = writeln!(self.sink, "#] {} ]#", self.record_name);
+        }
+    }
+}
+
+impl<W: Write> Deref for Scope<'_, W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        self.sink
+    }
+}
+
+impl<W: Write> DerefMut for Scope<'_, W> {
+    fn deref_mut(&mut self) -> &mut W {
+        self.sink
+    }
+}
+
+/// Write a one shot command record (`#= NAME arg: type(value) ... =#`) to the sink.
+/// The arguments are `name = value` pairs, the value being any [`RecordArg`]: `bool`, integers,
+/// floats, [`hex32`] and the other hexadecimal wrappers, [`id`], [`flags`], [`structure`], [`structures`],
+/// or arrays and slices of the scalars.
+#[macro_export]
+macro_rules! copar_record {
+    ($sink:expr, $record_name:ident $(, $arg_name:ident = $value:expr)* $(,)?) => {
+        $crate::write_record(
+            &mut $sink,
+            '=',
+            stringify!($record_name),
+            &[$((stringify!($arg_name), &$value as &dyn $crate::RecordArg)),*],
+        )
+    };
+}
+
+/// Open a scope command (`#[ NAME [#`) on the sink, returning the [`Scope`] guard.
+#[macro_export]
+macro_rules! scope {
+    ($sink:expr, $record_name:ident) => {
+        $crate::Scope::begin(&mut $sink, stringify!($record_name))
+    };
+}
+
+/// Write arguments of a scope command (`#- NAME arg: type(value) ... -#`), the arguments being
+/// given as with [`copar_record!`].
+#[macro_export]
+macro_rules! scope_record {
+    ($scope:expr $(, $arg_name:ident = $value:expr)* $(,)?) => {
+        $scope.record(&[$((stringify!($arg_name), &$value as &dyn $crate::RecordArg)),*])
+    };
+}
@@ -0,0 +1,90 @@
+use copar::{Parser, RustGeneration};
+use copar_emit::{
+    copar_record, flags, hex128, hex16, hex32, hex64, hex8, id, scope, scope_record, structure,
+    structures, RecordArg,
+};
+
+#[test]
+fn test_emitted_log_parsing() {
+    let mut log = String::from("#< test_sequence <#\n");
+    copar_record!(
+        log,
+        Cmd52,
+        write = true,
+        ln = 1u8,
+        add = hex32(0x1043),
+        data = hex8(0x80)
+    )
+    .unwrap();
+    copar_record!(
+        log,
+        Cmd53,
+        data = [hex8(0x80), hex8(0x5)],
+        dot = [-25i8, -69, 2],
+        num = 3.565f32,
+        ctrl = flags("CtrlBits", &["EN", "IRQ"]),
+        key = hex128(0x1ee3fb4fad554ce05da5708c1),
+        nonce = -3i128,
+    )
+    .unwrap();
+    let first_fields: [(&str, &dyn RecordArg); 2] = [("src", &hex32(0x10)), ("len", &4u16)];
+    let second_fields: [(&str, &dyn RecordArg); 2] = [("src", &hex32(0x20)), ("len", &8u16)];
+    let first_desc = structure("Desc", &first_fields);
+    let second_desc = structure("Desc", &second_fields);
+    copar_record!(
+        log,
+        Dma,
+        desc = first_desc,
+        descs = structures(&[first_desc, second_desc])
+    )
+    .unwrap();
+    {
+        let mut read_efuse = scope!(log, ReadEfuse).unwrap();
+        scope_record!(
+            read_efuse,
+            offset = hex16(0x5ea),
+            dv_sel = id("EfuseAccess", "DDV")
+        )
+        .unwrap();
+        copar_record!(
+            *read_efuse,
+            Cmd52,
+            write = false,
+            ln = 0u8,
+            add = hex32(0x1043),
+            data = hex8(0x0)
+        )
+        .unwrap();
+        scope_record!(read_efuse, map_ptr = hex64(0xda5708c1)).unwrap();
+    }
+    log += "#> test_sequence >#\n";
+
+    let expected_log = "\
+#< test_sequence <#
+#= Cmd52 write: bool(true) ln: u8(1) add: x32(0x1043) data: x8(0x80) =#
+#= Cmd53 data: x8([0x80,0x5]) dot: i8([-25,-69,2]) num: f32(3.565) ctrl: flags(CtrlBits::EN|CtrlBits::IRQ) key: x128(0x1ee3fb4fad554ce05da5708c1) nonce: i128(-3) =#
+#= Dma desc: Desc{src: x32(0x10) len: u16(4)} descs: [Desc{src: x32(0x10) len: u16(4)},Desc{src: x32(0x20) len: u16(8)}] =#
+#[ ReadEfuse [#
+#- ReadEfuse offset: x16(0x5ea) dv_sel: id(EfuseAccess::DDV) -#
+#= Cmd52 write: bool(false) ln: u8(0) add: x32(0x1043) data: x8(0x0) =#
+#- ReadEfuse map_ptr: x64(0xda5708c1) -#
+#] ReadEfuse ]#
+#> test_sequence >#
+";
+    assert_eq!(log, expected_log);
+
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    let mut rust_code = Vec::new();
+    model.compute_to_rust(&mut rust_code);
+    let rust_code = String::from_utf8(rust_code).unwrap();
+    assert!(rust_code.contains("map_ptr: 0xda5708c1"), "{rust_code}");
+    assert!(rust_code.contains("num: 3.565"), "{rust_code}");
+    assert!(
+        rust_code.contains("Dma { desc: &STRUCT_0, descs: STRUCT_ARRAY_2 }"),
+        "{rust_code}"
+    );
+    assert!(
+        rust_code.contains("ctrl: CtrlBits(CtrlBits::EN.0 | CtrlBits::IRQ.0)"),
+        "{rust_code}"
+    );
+}
@@ -0,0 +1,57 @@
+//! # Copar macros
+//! Procedural macros generating the copar operation tables at compile time.
+use std::{env, fs, path::PathBuf};
+
+use copar_core::{Parser, RustGeneration};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+/// Parse the log at compile time and expand to the items generated by
+/// `RustGeneration::compute_to_rust`. The path of the log is relative to the directory of the
+/// crate (`CARGO_MANIFEST_DIR`) and the crate is rebuilt when the log changes. The log errors are
+/// reported as compile errors. Re-exported by `copar` with its `macros` feature.
+///
+/// ```ignore
+/// mod init {
+///     copar::include_sequence!("logs/init.log");
+/// }
+/// ```
+#[proc_macro]
+pub fn include_sequence(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let log_path = parse_macro_input!(input as LitStr);
+    expand_include_sequence(&log_path.value())
+        .unwrap_or_else(|message| syn::Error::new(log_path.span(), message).to_compile_error())
+        .into()
+}
+
+fn expand_include_sequence(log_path: &str) -> Result<TokenStream, String> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = PathBuf::from(manifest_dir).join(log_path);
+    let log = fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let model = Parser::parse(log.as_bytes()).map_err(|e| format!("{log_path}: {e}"))?;
+
+    let mut generated_code = Vec::new();
+    model.compute_to_rust(&mut generated_code);
+    let generated_code: TokenStream = String::from_utf8_lossy(&generated_code)
+        .parse()
+        .map_err(|e| format!("{log_path}: Generated code is invalid: {e}"))?;
+    // Makes the compiler track the log, to rebuild the crate when it changes
+    let path = path.to_string_lossy();
+    Ok(quote! {
+        const _: &[u8] = include_bytes!(#path);
+        #generated_code
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn include_sequence_error_test() {
+        let error = expand_include_sequence("tests/logs/bad.log").unwrap_err();
+        assert!(error.starts_with("tests/logs/bad.log: line 2: "), "{error}");
+        assert!(expand_include_sequence("tests/logs/missing.log").is_err());
+    }
+}
@@ -0,0 +1,29 @@
+mod init {
+    copar::include_sequence!("tests/logs/init.log");
+}
+
+#[test]
+fn test_include_sequence() {
+    use init::{Cmd52, OperationVariant};
+
+    let commands: Vec<&Cmd52> = init::INIT
+        .iter()
+        .map(|operation| match operation {
+            OperationVariant::Cmd52(cmd) => *cmd,
+        })
+        .collect();
+    assert_eq!(
+        commands,
+        [
+            &Cmd52 {
+                add: 0x10,
+                data: 0x1
+            },
+            &Cmd52 {
+                add: 0x11,
+                data: 0x2
+            }
+        ]
+    );
+    assert_eq!(init::INIT_DELAYS_US, [0, 1000]);
+}
@@ -1,25 +1,74 @@
-use std::fs::File;
+mod project;
 
-use clap::{Args, Parser, Subcommand};
-use copar::{CGeneration, CSharpGeneration, RustGeneration};
+use std::{
+    fmt::Display,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use copar::{
+    CEmitterGeneration, CGeneration, CSharpGeneration, CheckSeverity, CompressionError,
+    FileParsingError, RustGeneration, Schema, SchemaError, TimestampFormat, UnknownSequenceError,
+};
+use project::{
+    ProjectConfig, ProjectConfigError, ProjectWatch, TargetConfig, TargetStatus,
+    DEFAULT_PROJECT_FILE_NAME,
+};
+use similar::TextDiff;
 
 #[derive(clap::Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
-    command: GenerationCommand,
+    command: Command,
+}
+
+#[derive(Args)]
+struct ModelArgs {
+    /// Capture the delays between operations from kernel log timestamps
+    #[arg(long)]
+    kernel_timestamps: bool,
+    /// Replace repeated operations with repeat operations
+    #[arg(long)]
+    compress: bool,
+    /// Comment each entry of the operation tables with the log line of its record
+    #[arg(long)]
+    annotate_sources: bool,
+    /// Validate the records against this schema file
+    #[arg(long, value_name = "FILE")]
+    schema: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Compare the generated code with the existing files and print the differences instead of
+    /// writing them
+    #[arg(long)]
+    check: bool,
 }
 
 #[derive(Args)]
 struct CSharpArgs {
     input_file_path: String,
     output_file_path: String,
+    #[command(flatten)]
+    model_args: ModelArgs,
+    #[command(flatten)]
+    output_args: OutputArgs,
 }
 
 #[derive(Args)]
 struct RustArgs {
     input_file_path: String,
     output_file_path: String,
+    #[command(flatten)]
+    model_args: ModelArgs,
+    #[command(flatten)]
+    output_args: OutputArgs,
 }
 
 #[derive(Args)]
@@ -27,61 +76,851 @@ struct CArgs {
     input_file_path: String,
     output_c_file_path: String,
     output_h_file_path: String,
+    #[command(flatten)]
+    model_args: ModelArgs,
+    #[command(flatten)]
+    output_args: OutputArgs,
+}
+
+#[derive(Args)]
+struct CEmitterArgs {
+    input_file_path: String,
+    output_h_file_path: String,
+    /// Capture the delays between operations from kernel log timestamps
+    #[arg(long)]
+    kernel_timestamps: bool,
+    /// Validate the records against this schema file
+    #[arg(long, value_name = "FILE")]
+    schema: Option<PathBuf>,
+    #[command(flatten)]
+    output_args: OutputArgs,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("outputs").required(true).multiple(true)))]
+struct GenerateArgs {
+    input_file_path: String,
+    /// Generate Rust code to this file
+    #[arg(long, value_name = "FILE", group = "outputs")]
+    rust: Vec<PathBuf>,
+    /// Generate C# code to this file
+    #[arg(long, value_name = "FILE", group = "outputs")]
+    cs: Vec<PathBuf>,
+    /// Generate C code to these source and header files
+    #[arg(long, num_args = 2, value_names = ["SOURCE", "HEADER"], group = "outputs")]
+    c: Vec<PathBuf>,
+    /// Generate a C header printing the records of the log to this file
+    #[arg(long, value_name = "FILE", group = "outputs")]
+    c_emitter: Vec<PathBuf>,
+    #[command(flatten)]
+    model_args: ModelArgs,
+    #[command(flatten)]
+    output_args: OutputArgs,
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    input_file_path: String,
+    /// Capture the delays between operations from kernel log timestamps
+    #[arg(long)]
+    kernel_timestamps: bool,
+    /// Validate the records against this schema file
+    #[arg(long, value_name = "FILE")]
+    schema: Option<PathBuf>,
+    /// Fail on warnings too
+    #[arg(long)]
+    deny_warnings: bool,
+}
+
+#[derive(Args)]
+struct ExtractArgs {
+    input_file_path: String,
+    output_file_path: String,
+    /// Keep the kernel log timestamps of the records
+    #[arg(long)]
+    kernel_timestamps: bool,
+    /// Precede each record with a comment giving its line in the original log
+    #[arg(long)]
+    line_numbers: bool,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    input_file_path: String,
+    /// Size of the pointers of the C target, used to estimate the ROM footprint
+    #[arg(long, default_value_t = 4)]
+    pointer_size: usize,
+    #[command(flatten)]
+    model_args: ModelArgs,
+}
+
+#[derive(Args)]
+struct BuildArgs {
+    /// Project file describing the outputs to generate
+    #[arg(long, default_value = DEFAULT_PROJECT_FILE_NAME)]
+    project: PathBuf,
+    /// Regenerate the outputs even if their inputs have not changed
+    #[arg(long)]
+    force: bool,
+    #[command(flatten)]
+    output_args: OutputArgs,
+}
+
+#[derive(Args)]
+struct WatchArgs {
+    /// Project file describing the outputs to generate
+    #[arg(long, default_value = DEFAULT_PROJECT_FILE_NAME)]
+    project: PathBuf,
+    /// Time between two checks of the files, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    interval_ms: u64,
 }
 
 #[derive(Subcommand)]
-enum GenerationCommand {
+enum Command {
     GenerateCSharp(CSharpArgs),
     GenerateC(CArgs),
     GenerateRust(RustArgs),
+    /// Generate a C header of functions printing the records of the log, to instrument C drivers
+    GenerateCEmitter(CEmitterArgs),
+    /// Generate code in several languages from a single parsing of the log
+    Generate(GenerateArgs),
+    /// Parse a log without generating anything and report its issues
+    Check(CheckArgs),
+    /// Write a minimal log keeping only the records of the sequences of the log
+    Extract(ExtractArgs),
+    /// Print a summary of the parsed log and an estimate of the generated C table size
+    Stats(StatsArgs),
+    /// Generate every output described in the project file
+    Build(BuildArgs),
+    /// Regenerate the outputs of the project file whenever a log or the project file changes
+    Watch(WatchArgs),
+}
+
+/// Path meaning stdin as input and stdout as output
+const STD_STREAM_PATH: &str = "-";
+
+/// Exit code of a `check` finding errors in the log
+const EXIT_VALIDATION_FAILURE: i32 = 1;
+/// Exit code of options that cannot be used together (sysexits `EX_USAGE`)
+const EXIT_USAGE_ERROR: i32 = 64;
+/// Exit code of a log that cannot be parsed (sysexits `EX_DATAERR`)
+const EXIT_DATA_ERROR: i32 = 65;
+/// Exit code of a file that cannot be read or written (sysexits `EX_IOERR`)
+const EXIT_IO_ERROR: i32 = 74;
+/// Exit code of a bad project file (sysexits `EX_CONFIG`)
+const EXIT_CONFIG_ERROR: i32 = 78;
+
+#[derive(Debug)]
+enum CliError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Parsing {
+        path: PathBuf,
+        error: FileParsingError,
+    },
+    Compression {
+        path: PathBuf,
+        error: CompressionError,
+    },
+    ProjectFormat(PathBuf, toml::de::Error),
+    Schema(PathBuf, SchemaError),
+    UnknownSequence(PathBuf, UnknownSequenceError),
+    CheckFailed,
+    OutdatedOutputs(usize),
+    CheckedStdout,
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Parsing { path, error } => write!(f, "{}: {error}", display_path(path)),
+            Self::Compression { path, error } => write!(f, "{}: {error}", display_path(path)),
+            Self::ProjectFormat(path, error) => write!(f, "{}: {error}", path.display()),
+            Self::Schema(path, error) => write!(f, "{}: {error}", path.display()),
+            Self::UnknownSequence(path, error) => write!(f, "{}: {error}", path.display()),
+            Self::CheckFailed => write!(f, "Check failed"),
+            Self::OutdatedOutputs(nb_outputs) => {
+                write!(f, "{nb_outputs} generated file(s) are not up to date")
+            }
+            Self::CheckedStdout => write!(f, "--check cannot compare an output written to stdout"),
+        }
+    }
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Self::Io { .. } => EXIT_IO_ERROR,
+            Self::Parsing { .. } | Self::Compression { .. } => EXIT_DATA_ERROR,
+            Self::ProjectFormat(..) | Self::Schema(..) | Self::UnknownSequence(..) => {
+                EXIT_CONFIG_ERROR
+            }
+            Self::CheckFailed | Self::OutdatedOutputs(_) => EXIT_VALIDATION_FAILURE,
+            Self::CheckedStdout => EXIT_USAGE_ERROR,
+        }
+    }
+}
+
+/// Path of an input file as shown in the diagnostics
+fn display_path(path: &Path) -> std::path::Display<'_> {
+    if path == Path::new(STD_STREAM_PATH) {
+        Path::new("<stdin>").display()
+    } else {
+        path.display()
+    }
+}
+
+/// Read the whole input file, or stdin for `-`
+fn read_input(input_file_path: &Path) -> Result<String, CliError> {
+    let result = if input_file_path == Path::new(STD_STREAM_PATH) {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map(|_| content)
+    } else {
+        fs::read_to_string(input_file_path)
+    };
+    result.map_err(|error| CliError::Io {
+        path: display_path(input_file_path).to_string().into(),
+        error,
+    })
+}
+
+/// Generated file content waiting to be written
+struct Output {
+    path: PathBuf,
+    content: Vec<u8>,
+}
+
+impl Output {
+    fn is_stdout(&self) -> bool {
+        self.path == Path::new(STD_STREAM_PATH)
+    }
+
+    /// File replaced by the output, the target of the symlinks being replaced rather than the
+    /// links, or `None` for the files written in place such as devices or pipes
+    fn replaced_path(&self) -> Result<Option<PathBuf>, CliError> {
+        match fs::metadata(&self.path) {
+            Ok(metadata) if metadata.is_file() => fs::canonicalize(&self.path)
+                .map(Some)
+                .map_err(|error| self.io_error(error)),
+            Ok(_) => Ok(None),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Some(self.path.clone()))
+            }
+            Err(error) => Err(self.io_error(error)),
+        }
+    }
+
+    fn io_error(&self, error: std::io::Error) -> CliError {
+        CliError::Io {
+            path: if self.is_stdout() {
+                PathBuf::from("<stdout>")
+            } else {
+                self.path.clone()
+            },
+            error,
+        }
+    }
+}
+
+/// Print the unified diff between the existing files and the generated outputs, failing if any
+/// of them differs. Outputs written to stdout have no existing content to compare with.
+fn check_outputs(outputs: &[Output]) -> Result<(), CliError> {
+    if outputs.iter().any(Output::is_stdout) {
+        return Err(CliError::CheckedStdout);
+    }
+
+    let mut nb_outdated_outputs = 0;
+    for output in outputs.iter() {
+        let existing_content = match fs::read(&output.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(output.io_error(error)),
+        };
+        if existing_content == output.content {
+            continue;
+        }
+
+        nb_outdated_outputs += 1;
+        let existing_content = String::from_utf8_lossy(&existing_content);
+        let content = String::from_utf8_lossy(&output.content);
+        let path = output.path.display().to_string();
+        print!(
+            "{}",
+            TextDiff::from_lines(&existing_content, &content)
+                .unified_diff()
+                .header(&path, &path)
+        );
+    }
+
+    if nb_outdated_outputs > 0 {
+        return Err(CliError::OutdatedOutputs(nb_outdated_outputs));
+    }
+    Ok(())
 }
 
-fn generate_rust(input_log_file_path: &str, output_file_path: &str) {
-    let input_file = File::open(input_log_file_path).unwrap();
-    let mut output_file = File::create(output_file_path).unwrap();
+/// Write or check the outputs according to the output options
+fn emit_outputs(outputs: &[Output], output_args: &OutputArgs) -> Result<(), CliError> {
+    if output_args.check {
+        check_outputs(outputs)
+    } else {
+        write_outputs(outputs)
+    }
+}
 
-    let model = copar::Parser::parse(input_file).unwrap();
-    model.compute_to_rust(&mut output_file);
+/// Temporary file written next to a replaced file before renaming it
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut file_name = std::ffi::OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(file_name)
 }
-fn generate_csharp(input_log_file_path: &str, output_file_path: &str) {
-    let input_file = File::open(input_log_file_path).unwrap();
-    let mut output_file = File::create(output_file_path).unwrap();
 
-    let model = copar::Parser::parse(input_file).unwrap();
-    model.compute_to_cs(&mut output_file);
+/// Write all the outputs. Regular files are written to a temporary file first, then renamed to
+/// replace the output, so that no file is left half-written and none is replaced if one of the
+/// temporary files cannot be written; a failed rename still leaves the outputs renamed before it
+/// replaced. The other files, such as devices or pipes, are written in place.
+fn write_outputs(outputs: &[Output]) -> Result<(), CliError> {
+    let mut replaced_outputs = Vec::new();
+    let mut in_place_outputs = Vec::new();
+    for output in outputs.iter().filter(|output| !output.is_stdout()) {
+        match output.replaced_path()? {
+            Some(path) => replaced_outputs.push((output, temporary_path(&path), path)),
+            None => in_place_outputs.push(output),
+        }
+    }
+
+    for (index, (output, temporary_path, _)) in replaced_outputs.iter().enumerate() {
+        if let Err(error) = fs::write(temporary_path, &output.content) {
+            for (_, written_path, _) in replaced_outputs[..=index].iter() {
+                let _ = fs::remove_file(written_path);
+            }
+            return Err(output.io_error(error));
+        }
+    }
+    for (output, temporary_path, path) in replaced_outputs.iter() {
+        fs::rename(temporary_path, path).map_err(|error| output.io_error(error))?;
+    }
+    for output in in_place_outputs {
+        fs::write(&output.path, &output.content).map_err(|error| output.io_error(error))?;
+    }
+
+    for output in outputs.iter().filter(|output| output.is_stdout()) {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(&output.content)
+            .and_then(|_| stdout.flush())
+            .map_err(|error| output.io_error(error))?;
+    }
+    Ok(())
 }
 
-fn generate_c(input_log_file_path: &str, output_c_file_path: &str, output_h_file_path: &str) {
-    let input_file = File::open(input_log_file_path).unwrap();
-    let mut output_c_file = File::create(output_c_file_path).unwrap();
-    let mut output_h_file = File::create(output_h_file_path).unwrap();
+fn load_schema(schema_file_path: &Path) -> Result<Schema, CliError> {
+    let content = fs::read_to_string(schema_file_path).map_err(|error| CliError::Io {
+        path: schema_file_path.to_path_buf(),
+        error,
+    })?;
+    Schema::from_toml(&content)
+        .map_err(|error| CliError::Schema(schema_file_path.to_path_buf(), error))
+}
 
-    let model = copar::Parser::parse(input_file).unwrap();
-    model.compute_to_c(&mut output_c_file, &mut output_h_file);
+fn new_parser(
+    input_log_file_path: &Path,
+    kernel_timestamps: bool,
+    schema_file_path: Option<&Path>,
+) -> Result<copar::Parser, CliError> {
+    let input = read_input(input_log_file_path)?;
+    let mut parser = copar::Parser::new(input.as_bytes());
+    // Only the file name is kept for the generated files not to depend on the build machine
+    if let Some(file_name) = input_log_file_path
+        .file_name()
+        .filter(|_| input_log_file_path != Path::new(STD_STREAM_PATH))
+    {
+        parser = parser.with_file_name(file_name.to_string_lossy());
+    }
+    if kernel_timestamps {
+        parser = parser.with_timestamps(TimestampFormat::KernelLog);
+    }
+    if let Some(schema_file_path) = schema_file_path {
+        parser = parser.with_schema(load_schema(schema_file_path)?);
+    }
+    Ok(parser)
+}
+
+fn parse_model(
+    input_log_file_path: impl AsRef<Path>,
+    model_args: &ModelArgs,
+) -> Result<copar::Model, CliError> {
+    parse_selected_model(input_log_file_path, model_args, None)
+}
+
+/// Parse the log, keeping only the sequences listed in the given file when there are some. The
+/// sequences are selected before compressing, in the same order as `copar::build::Config`.
+fn parse_selected_model(
+    input_log_file_path: impl AsRef<Path>,
+    model_args: &ModelArgs,
+    sequences: Option<(&[String], &Path)>,
+) -> Result<copar::Model, CliError> {
+    let path = input_log_file_path.as_ref();
+    let parser = new_parser(
+        path,
+        model_args.kernel_timestamps,
+        model_args.schema.as_deref(),
+    )?;
+    let mut model = parser.parse_file().map_err(|error| CliError::Parsing {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    if let Some((sequence_names, listing_file_path)) = sequences {
+        model
+            .select_sequences(sequence_names)
+            .map_err(|error| CliError::UnknownSequence(listing_file_path.to_path_buf(), error))?;
+    }
+    model.set_source_annotations(model_args.annotate_sources);
+    if model_args.compress {
+        model.compress().map_err(|error| CliError::Compression {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    }
+    Ok(model)
+}
+
+fn render_rust(model: &copar::Model, output_file_path: impl Into<PathBuf>) -> Output {
+    let mut content = Vec::new();
+    model.compute_to_rust(&mut content);
+    Output {
+        path: output_file_path.into(),
+        content,
+    }
+}
+
+fn render_csharp(model: &copar::Model, output_file_path: impl Into<PathBuf>) -> Output {
+    let mut content = Vec::new();
+    model.compute_to_cs(&mut content);
+    Output {
+        path: output_file_path.into(),
+        content,
+    }
+}
+
+fn render_c(
+    model: &copar::Model,
+    output_c_file_path: impl Into<PathBuf>,
+    output_h_file_path: impl Into<PathBuf>,
+) -> [Output; 2] {
+    let mut content_c = Vec::new();
+    let mut content_h = Vec::new();
+    model.compute_to_c(&mut content_c, &mut content_h);
+    [
+        Output {
+            path: output_c_file_path.into(),
+            content: content_c,
+        },
+        Output {
+            path: output_h_file_path.into(),
+            content: content_h,
+        },
+    ]
+}
+
+fn generate_rust(
+    input_log_file_path: &str,
+    output_file_path: &str,
+    model_args: &ModelArgs,
+    output_args: &OutputArgs,
+) -> Result<(), CliError> {
+    let model = parse_model(input_log_file_path, model_args)?;
+    emit_outputs(&[render_rust(&model, output_file_path)], output_args)
+}
+
+fn generate_csharp(
+    input_log_file_path: &str,
+    output_file_path: &str,
+    model_args: &ModelArgs,
+    output_args: &OutputArgs,
+) -> Result<(), CliError> {
+    let model = parse_model(input_log_file_path, model_args)?;
+    emit_outputs(&[render_csharp(&model, output_file_path)], output_args)
+}
+
+fn generate_c(
+    input_log_file_path: &str,
+    output_c_file_path: &str,
+    output_h_file_path: &str,
+    model_args: &ModelArgs,
+    output_args: &OutputArgs,
+) -> Result<(), CliError> {
+    let model = parse_model(input_log_file_path, model_args)?;
+    emit_outputs(
+        &render_c(&model, output_c_file_path, output_h_file_path),
+        output_args,
+    )
+}
+
+fn render_c_emitter(model: &copar::Model, output_h_file_path: impl Into<PathBuf>) -> Output {
+    let mut content = Vec::new();
+    model.compute_to_c_emitter(&mut content);
+    Output {
+        path: output_h_file_path.into(),
+        content,
+    }
+}
+
+fn generate_c_emitter(c_emitter_args: &CEmitterArgs) -> Result<(), CliError> {
+    let model_args = ModelArgs {
+        kernel_timestamps: c_emitter_args.kernel_timestamps,
+        compress: false,
+        annotate_sources: false,
+        schema: c_emitter_args.schema.clone(),
+    };
+    let model = parse_model(&c_emitter_args.input_file_path, &model_args)?;
+    emit_outputs(
+        &[render_c_emitter(&model, &c_emitter_args.output_h_file_path)],
+        &c_emitter_args.output_args,
+    )
+}
+
+/// Generate every requested output from a single parsing of the log
+fn generate(generate_args: &GenerateArgs) -> Result<(), CliError> {
+    let model = parse_model(&generate_args.input_file_path, &generate_args.model_args)?;
+    let mut outputs = Vec::new();
+    for rust_path in generate_args.rust.iter() {
+        outputs.push(render_rust(&model, rust_path));
+    }
+    for c_sharp_path in generate_args.cs.iter() {
+        outputs.push(render_csharp(&model, c_sharp_path));
+    }
+    for c_paths in generate_args.c.chunks(2) {
+        outputs.extend(render_c(&model, &c_paths[0], &c_paths[1]));
+    }
+    for c_emitter_path in generate_args.c_emitter.iter() {
+        outputs.push(render_c_emitter(&model, c_emitter_path));
+    }
+    emit_outputs(&outputs, &generate_args.output_args)
+}
+
+fn build_target(
+    target: &TargetConfig,
+    project_file_path: &Path,
+    output_args: &OutputArgs,
+) -> Result<(), CliError> {
+    let model_args = ModelArgs {
+        kernel_timestamps: target.kernel_timestamps,
+        compress: target.compress,
+        annotate_sources: target.annotate_sources,
+        schema: target.schema.clone(),
+    };
+    let model = parse_selected_model(
+        &target.input,
+        &model_args,
+        target
+            .sequences
+            .as_deref()
+            .map(|sequences| (sequences, project_file_path)),
+    )?;
+
+    if !output_args.check {
+        for output_path in target.output_paths() {
+            if let Some(output_dir) = output_path.parent() {
+                fs::create_dir_all(output_dir).map_err(|error| CliError::Io {
+                    path: output_dir.to_path_buf(),
+                    error,
+                })?;
+            }
+        }
+    }
+    let mut outputs = Vec::new();
+    if let Some(rust_path) = target.rust.as_ref() {
+        outputs.push(render_rust(&model, rust_path));
+    }
+    if let Some(c_sharp_path) = target.c_sharp.as_ref() {
+        outputs.push(render_csharp(&model, c_sharp_path));
+    }
+    if let Some(c) = target.c.as_ref() {
+        outputs.extend(render_c(&model, &c.source, &c.header));
+    }
+    if let Some(c_emitter_path) = target.c_emitter.as_ref() {
+        outputs.push(render_c_emitter(&model, c_emitter_path));
+    }
+    emit_outputs(&outputs, output_args)
+}
+
+fn project_error(project_file_path: &Path, e: ProjectConfigError) -> CliError {
+    match e {
+        ProjectConfigError::Io(error) => CliError::Io {
+            path: project_file_path.to_path_buf(),
+            error,
+        },
+        ProjectConfigError::BadFormat(error) => {
+            CliError::ProjectFormat(project_file_path.to_path_buf(), error)
+        }
+    }
+}
+
+fn load_project(project_file_path: &Path) -> Result<ProjectConfig, CliError> {
+    ProjectConfig::load(project_file_path).map_err(|e| project_error(project_file_path, e))
+}
+
+fn build(build_args: &BuildArgs) -> Result<(), CliError> {
+    let project_config = load_project(&build_args.project)?;
+    let mut nb_outdated_outputs = 0;
+    for target in project_config.targets.iter() {
+        if build_args.output_args.check {
+            // The content of every output is compared, whatever the modification times
+            match build_target(target, &build_args.project, &build_args.output_args) {
+                Err(CliError::OutdatedOutputs(nb_outputs)) => nb_outdated_outputs += nb_outputs,
+                result => result?,
+            }
+        } else if !build_args.force && target.is_up_to_date(&build_args.project) {
+            println!("{}: up to date", target.input.display());
+        } else {
+            println!("{}: generating", target.input.display());
+            build_target(target, &build_args.project, &build_args.output_args)?;
+        }
+    }
+
+    if nb_outdated_outputs > 0 {
+        return Err(CliError::OutdatedOutputs(nb_outdated_outputs));
+    }
+    Ok(())
+}
+
+/// Poll the project file and the logs of its targets, regenerating the targets whose log or
+/// project file changed. The errors are reported without stopping the watch.
+fn watch(watch_args: &WatchArgs) -> Result<(), CliError> {
+    let output_args = OutputArgs { check: false };
+    let project_file_path = &watch_args.project;
+    let mut project_watch = ProjectWatch::default();
+
+    loop {
+        match project_watch.poll(project_file_path) {
+            Ok(targets) => {
+                for (target, status) in targets.iter() {
+                    if *status == TargetStatus::UpToDate {
+                        println!("{}: up to date", target.input.display());
+                        continue;
+                    }
+                    println!("{}: generating", target.input.display());
+                    if let Err(e) = build_target(target, project_file_path, &output_args) {
+                        eprintln!("error: {e}");
+                    }
+                }
+            }
+            Err(e) => eprintln!("error: {}", project_error(project_file_path, e)),
+        }
+        thread::sleep(Duration::from_millis(watch_args.interval_ms));
+    }
+}
+
+fn extract(extract_args: &ExtractArgs) -> Result<(), CliError> {
+    let parser = new_parser(
+        Path::new(&extract_args.input_file_path),
+        extract_args.kernel_timestamps,
+        None,
+    )?;
+    let output = Output {
+        path: PathBuf::from(&extract_args.output_file_path),
+        content: parser.extract(extract_args.line_numbers).into_bytes(),
+    };
+    write_outputs(&[output])
+}
+
+fn print_stats(stats_args: &StatsArgs) -> Result<(), CliError> {
+    let model = parse_model(&stats_args.input_file_path, &stats_args.model_args)?;
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", model.stats(stats_args.pointer_size))
+        .and_then(|_| stdout.flush())
+        .map_err(|error| CliError::Io {
+            path: PathBuf::from("<stdout>"),
+            error,
+        })
+}
+
+/// Report the issues of the log, failing if it does not pass the check
+fn check(check_args: &CheckArgs) -> Result<(), CliError> {
+    let input_path = Path::new(&check_args.input_file_path);
+    let parser = new_parser(
+        input_path,
+        check_args.kernel_timestamps,
+        check_args.schema.as_deref(),
+    )?;
+    let issues = parser.check();
+
+    for issue in issues.iter() {
+        match issue.line_nb {
+            Some(line_nb) => eprintln!(
+                "{}:{}: {}: {}",
+                display_path(input_path),
+                line_nb,
+                issue.severity,
+                issue.kind
+            ),
+            None => eprintln!(
+                "{}: {}: {}",
+                display_path(input_path),
+                issue.severity,
+                issue.kind
+            ),
+        }
+    }
+
+    let nb_errors = issues
+        .iter()
+        .filter(|issue| issue.severity == CheckSeverity::Error)
+        .count();
+    let nb_warnings = issues.len() - nb_errors;
+    eprintln!("{nb_errors} error(s), {nb_warnings} warning(s)");
+
+    if nb_errors > 0 || (nb_warnings > 0 && check_args.deny_warnings) {
+        return Err(CliError::CheckFailed);
+    }
+    Ok(())
+}
+
+fn run(command: &Command) -> Result<(), CliError> {
+    match command {
+        Command::GenerateCSharp(cs_args) => generate_csharp(
+            cs_args.input_file_path.as_str(),
+            cs_args.output_file_path.as_str(),
+            &cs_args.model_args,
+            &cs_args.output_args,
+        ),
+        Command::GenerateC(c_args) => generate_c(
+            c_args.input_file_path.as_str(),
+            c_args.output_c_file_path.as_str(),
+            c_args.output_h_file_path.as_str(),
+            &c_args.model_args,
+            &c_args.output_args,
+        ),
+        Command::GenerateRust(rust_args) => generate_rust(
+            rust_args.input_file_path.as_str(),
+            rust_args.output_file_path.as_str(),
+            &rust_args.model_args,
+            &rust_args.output_args,
+        ),
+        Command::GenerateCEmitter(c_emitter_args) => generate_c_emitter(c_emitter_args),
+        Command::Generate(generate_args) => generate(generate_args),
+        Command::Stats(stats_args) => print_stats(stats_args),
+        Command::Build(build_args) => build(build_args),
+        Command::Watch(watch_args) => watch(watch_args),
+        Command::Check(check_args) => check(check_args),
+        Command::Extract(extract_args) => extract(extract_args),
+    }
 }
 
 fn main() {
     let args = Cli::parse();
 
-    match &args.command {
-        GenerationCommand::GenerateCSharp(cs_args) => {
-            generate_csharp(
-                cs_args.input_file_path.as_str(),
-                cs_args.output_file_path.as_str(),
-            );
-        }
-        GenerationCommand::GenerateC(c_args) => {
-            generate_c(
-                c_args.input_file_path.as_str(),
-                c_args.output_c_file_path.as_str(),
-                c_args.output_h_file_path.as_str(),
-            );
-        }
-        GenerationCommand::GenerateRust(rust_args) => {
-            generate_rust(
-                rust_args.input_file_path.as_str(),
-                rust_args.output_file_path.as_str(),
-            );
+    if let Err(e) = run(&args.command) {
+        // The issues of a failed check are already reported
+        if !matches!(e, CliError::CheckFailed) {
+            eprintln!("error: {e}");
         }
+        std::process::exit(e.exit_code());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Temporary directory of a test, removed even when the test fails
+    pub(crate) struct TestDir(PathBuf);
+
+    impl TestDir {
+        pub(crate) fn new(name: &str) -> TestDir {
+            let path = std::env::temp_dir().join(format!("copar_{name}_{}", std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TestDir(path)
+        }
+
+        pub(crate) fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn output(path: PathBuf, content: &str) -> Output {
+        Output {
+            path,
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn write_outputs_test() {
+        let dir = TestDir::new("write_outputs_test");
+        let existing_path = dir.path().join("existing.rs");
+        let new_path = dir.path().join("new.rs");
+        fs::write(&existing_path, "old").unwrap();
+        write_outputs(&[
+            output(existing_path.clone(), "existing"),
+            output(new_path.clone(), "new"),
+        ])
+        .unwrap();
+        assert_eq!(fs::read_to_string(&existing_path).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(&new_path).unwrap(), "new");
+
+        // A symlink is kept, the file it points to is replaced
+        #[cfg(unix)]
+        {
+            let link_path = dir.path().join("link.rs");
+            std::os::unix::fs::symlink(&existing_path, &link_path).unwrap();
+            write_outputs(&[output(link_path.clone(), "linked")]).unwrap();
+            assert!(fs::symlink_metadata(&link_path).unwrap().is_symlink());
+            assert_eq!(fs::read_to_string(&existing_path).unwrap(), "linked");
+        }
+
+        // No output is replaced when one of them cannot be written
+        let error = write_outputs(&[
+            output(new_path.clone(), "replaced"),
+            output(dir.path().join("missing").join("init.rs"), "unwritten"),
+        ])
+        .unwrap_err();
+        assert!(matches!(error, CliError::Io { .. }));
+        assert_eq!(error.exit_code(), EXIT_IO_ERROR);
+        assert_eq!(fs::read_to_string(&new_path).unwrap(), "new");
+
+        // Every temporary file is renamed or removed
+        assert!(fs::read_dir(dir.path()).unwrap().all(|entry| !entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .ends_with(".tmp")));
+    }
+
+    #[test]
+    fn check_outputs_test() {
+        let dir = TestDir::new("check_outputs_test");
+        let path = dir.path().join("init.rs");
+        fs::write(&path, "line\n").unwrap();
+        assert!(check_outputs(&[output(path.clone(), "line\n")]).is_ok());
+
+        let error = check_outputs(&[
+            output(path.clone(), "other line\n"),
+            output(dir.path().join("missing.rs"), "line\n"),
+        ])
+        .unwrap_err();
+        assert!(matches!(error, CliError::OutdatedOutputs(2)));
+        assert_eq!(error.exit_code(), EXIT_VALIDATION_FAILURE);
+        // The files are left untouched
+        assert_eq!(fs::read_to_string(&path).unwrap(), "line\n");
+        assert!(!dir.path().join("missing.rs").exists());
+
+        let error = check_outputs(&[output(PathBuf::from(STD_STREAM_PATH), "")]).unwrap_err();
+        assert!(matches!(error, CliError::CheckedStdout));
+        assert_eq!(error.exit_code(), EXIT_USAGE_ERROR);
     }
 }
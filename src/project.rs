@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::Deserialize;
+
+/// Default name of the project configuration file
+pub(crate) const DEFAULT_PROJECT_FILE_NAME: &str = "copar.toml";
+
+/// Project configuration file, describing every output to generate from the logs
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ProjectConfig {
+    #[serde(rename = "target", default)]
+    pub(crate) targets: Vec<TargetConfig>,
+}
+
+/// Outputs generated from one log
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TargetConfig {
+    pub(crate) input: PathBuf,
+    /// Sequences of the log to generate, all of them when absent
+    pub(crate) sequences: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) kernel_timestamps: bool,
+    #[serde(default)]
+    pub(crate) compress: bool,
+    #[serde(default)]
+    pub(crate) annotate_sources: bool,
+    /// Schema file validating the records of the log
+    pub(crate) schema: Option<PathBuf>,
+    pub(crate) rust: Option<PathBuf>,
+    pub(crate) c_sharp: Option<PathBuf>,
+    pub(crate) c: Option<COutputConfig>,
+    /// C header printing the records of the log
+    pub(crate) c_emitter: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct COutputConfig {
+    pub(crate) source: PathBuf,
+    pub(crate) header: PathBuf,
+}
+
+#[derive(Debug)]
+pub(crate) enum ProjectConfigError {
+    Io(std::io::Error),
+    BadFormat(toml::de::Error),
+}
+
+impl ProjectConfig {
+    /// Read the project file. The relative paths of the file are relative to its directory.
+    pub(crate) fn load(project_file_path: &Path) -> Result<ProjectConfig, ProjectConfigError> {
+        let content = fs::read_to_string(project_file_path).map_err(ProjectConfigError::Io)?;
+        let mut project_config = Self::from_toml(&content)?;
+        let project_dir = project_file_path.parent().unwrap_or(Path::new(""));
+        for target in project_config.targets.iter_mut() {
+            target.resolve_paths(project_dir);
+        }
+        Ok(project_config)
+    }
+
+    fn from_toml(content: &str) -> Result<ProjectConfig, ProjectConfigError> {
+        toml::from_str(content).map_err(ProjectConfigError::BadFormat)
+    }
+}
+
+/// Modification time of the file, `None` if it does not exist
+pub(crate) fn modification_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl TargetConfig {
+    fn resolve_paths(&mut self, project_dir: &Path) {
+        let resolve = |path: &mut PathBuf| *path = project_dir.join(&*path);
+        resolve(&mut self.input);
+        if let Some(schema) = self.schema.as_mut() {
+            resolve(schema);
+        }
+        if let Some(rust) = self.rust.as_mut() {
+            resolve(rust);
+        }
+        if let Some(c_sharp) = self.c_sharp.as_mut() {
+            resolve(c_sharp);
+        }
+        if let Some(c) = self.c.as_mut() {
+            resolve(&mut c.source);
+            resolve(&mut c.header);
+        }
+        if let Some(c_emitter) = self.c_emitter.as_mut() {
+            resolve(c_emitter);
+        }
+    }
+
+    /// Paths of every file generated by the target
+    pub(crate) fn output_paths(&self) -> Vec<&Path> {
+        let mut output_paths: Vec<&Path> = Vec::new();
+        output_paths.extend(self.rust.as_deref());
+        output_paths.extend(self.c_sharp.as_deref());
+        if let Some(c) = self.c.as_ref() {
+            output_paths.push(&c.source);
+            output_paths.push(&c.header);
+        }
+        output_paths.extend(self.c_emitter.as_deref());
+        output_paths
+    }
+
+    /// Log and schema files read by the target
+    fn input_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.input).chain(self.schema.as_ref())
+    }
+
+    /// Whether every output exists and is more recent than the log and the project file
+    pub(crate) fn is_up_to_date(&self, project_file_path: &Path) -> bool {
+        let Some(input_time) = [self.input.as_path(), project_file_path]
+            .into_iter()
+            .chain(self.schema.as_deref())
+            .map(modification_time)
+            .collect::<Option<Vec<SystemTime>>>()
+            .and_then(|times| times.into_iter().max())
+        else {
+            return false;
+        };
+        self.output_paths().into_iter().all(|output_path| {
+            modification_time(output_path).is_some_and(|time| time >= input_time)
+        })
+    }
+}
+
+/// State of a target found by a poll of the watched project
+#[derive(Debug, PartialEq)]
+pub(crate) enum TargetStatus {
+    /// The outputs are more recent than the inputs, only reported by the first poll
+    UpToDate,
+    /// The target has to be regenerated
+    Changed,
+}
+
+/// Modification times of the project file and of the inputs of its targets, polled to find the
+/// targets to regenerate
+#[derive(Default)]
+pub(crate) struct ProjectWatch {
+    /// Modification times at the previous poll, absent before the first one
+    last_modification_times: Option<HashMap<PathBuf, Option<SystemTime>>>,
+}
+
+impl ProjectWatch {
+    /// Return the targets whose log, schema or project file changed since the previous poll. The
+    /// first poll returns every target, up to date when its outputs are more recent than its
+    /// inputs. A project file that cannot be loaded is only reported when it changed.
+    pub(crate) fn poll(
+        &mut self,
+        project_file_path: &Path,
+    ) -> Result<Vec<(TargetConfig, TargetStatus)>, ProjectConfigError> {
+        let last_modification_times = self.last_modification_times.take();
+        let is_first_poll = last_modification_times.is_none();
+        let last_modification_times = last_modification_times.unwrap_or_default();
+        let has_changed = |path: &Path, modification_times: &HashMap<PathBuf, _>| {
+            last_modification_times.get(path) != modification_times.get(path)
+        };
+
+        let mut modification_times = HashMap::new();
+        modification_times.insert(
+            project_file_path.to_path_buf(),
+            modification_time(project_file_path),
+        );
+        let is_project_changed = has_changed(project_file_path, &modification_times);
+        let project_config = match ProjectConfig::load(project_file_path) {
+            Ok(project_config) => project_config,
+            Err(e) => {
+                self.last_modification_times = Some(modification_times);
+                return if is_project_changed {
+                    Err(e)
+                } else {
+                    Ok(Vec::new())
+                };
+            }
+        };
+
+        for input_path in project_config
+            .targets
+            .iter()
+            .flat_map(TargetConfig::input_paths)
+        {
+            modification_times.insert(input_path.clone(), modification_time(input_path));
+        }
+        let targets = project_config
+            .targets
+            .into_iter()
+            .filter(|target| {
+                is_project_changed
+                    || target
+                        .input_paths()
+                        .any(|input_path| has_changed(input_path, &modification_times))
+            })
+            .map(|target| {
+                let status = if is_first_poll && target.is_up_to_date(project_file_path) {
+                    TargetStatus::UpToDate
+                } else {
+                    TargetStatus::Changed
+                };
+                (target, status)
+            })
+            .collect();
+        self.last_modification_times = Some(modification_times);
+        Ok(targets)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::TestDir;
+    use std::time::Duration;
+
+    #[test]
+    fn project_config_test() {
+        let content = r#"
+[[target]]
+input = "logs/init.log"
+sequences = ["power_on"]
+compress = true
+annotate_sources = true
+schema = "schema.toml"
+rust = "src/init.rs"
+c = { source = "c/init.c", header = "c/init.h" }
+"#;
+        let mut project_config = ProjectConfig::from_toml(content).unwrap();
+        let target = &mut project_config.targets[0];
+        target.resolve_paths(Path::new("project"));
+
+        assert_eq!(target.input, Path::new("project/logs/init.log"));
+        assert_eq!(target.sequences, Some(vec![String::from("power_on")]));
+        assert!(target.compress && target.annotate_sources && !target.kernel_timestamps);
+        assert_eq!(
+            target.schema.as_deref(),
+            Some(Path::new("project/schema.toml"))
+        );
+        assert_eq!(
+            target.output_paths(),
+            [
+                Path::new("project/src/init.rs"),
+                Path::new("project/c/init.c"),
+                Path::new("project/c/init.h")
+            ]
+        );
+        assert!(
+            ProjectConfig::from_toml("[[target]]\ninput = \"a.log\"\ncsharp = \"a.cs\"").is_err()
+        );
+    }
+
+    #[test]
+    fn project_watch_test() {
+        let test_dir = TestDir::new("watch_test");
+        let dir = test_dir.path();
+        let project_file_path = dir.join(DEFAULT_PROJECT_FILE_NAME);
+        fs::write(
+            &project_file_path,
+            "[[target]]\ninput = \"init.log\"\nrust = \"init.rs\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("init.log"), "#< init <#\n#> init >#\n").unwrap();
+        let set_modification_time = |file_name: &str, time: SystemTime| {
+            let file = fs::File::options()
+                .write(true)
+                .open(dir.join(file_name))
+                .unwrap();
+            file.set_modified(time).unwrap();
+        };
+        let now = SystemTime::now();
+        set_modification_time(DEFAULT_PROJECT_FILE_NAME, now - Duration::from_secs(10));
+        set_modification_time("init.log", now - Duration::from_secs(10));
+        let statuses = |project_watch: &mut ProjectWatch| -> Vec<TargetStatus> {
+            project_watch
+                .poll(&project_file_path)
+                .unwrap()
+                .into_iter()
+                .map(|(_, status)| status)
+                .collect()
+        };
+
+        // The first poll returns every target, whether generated or not
+        let mut project_watch = ProjectWatch::default();
+        assert_eq!(statuses(&mut project_watch), [TargetStatus::Changed]);
+        assert_eq!(statuses(&mut project_watch), []);
+        fs::write(dir.join("init.rs"), "").unwrap();
+        assert_eq!(
+            statuses(&mut ProjectWatch::default()),
+            [TargetStatus::UpToDate]
+        );
+
+        set_modification_time("init.log", now);
+        assert_eq!(statuses(&mut project_watch), [TargetStatus::Changed]);
+        assert_eq!(statuses(&mut project_watch), []);
+
+        // A broken project file is reported once
+        fs::write(&project_file_path, "[[target]]\n").unwrap();
+        assert!(project_watch.poll(&project_file_path).is_err());
+        assert!(project_watch.poll(&project_file_path).unwrap().is_empty());
+    }
+}
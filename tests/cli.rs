@@ -0,0 +1,109 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+use copar::{Parser, RustGeneration};
+
+const LOG: &str = "\
+#< init <#
+#= Cmd52 add: x32(0x10) =#
+#> init >#
+";
+
+/// Temporary directory of a test, removed even when the test fails
+struct TestDir(PathBuf);
+
+impl TestDir {
+    fn new(name: &str) -> TestDir {
+        let path = std::env::temp_dir().join(format!("copar_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Run copar with these arguments and this content on stdin
+fn copar(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_copar"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn generated_rust(log: &str) -> String {
+    let mut output_rs = Vec::new();
+    Parser::parse(log.as_bytes())
+        .unwrap()
+        .compute_to_rust(&mut output_rs);
+    String::from_utf8(output_rs).unwrap()
+}
+
+#[test]
+fn test_std_streams() {
+    let output = copar(&["generate-rust", "-", "-"], LOG);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        generated_rust(LOG)
+    );
+
+    let output = copar(&["generate-rust", "-", "-"], "#< init <#\n#= Cmd52 =#\n");
+    assert_eq!(output.status.code(), Some(65));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("error: <stdin>:"));
+
+    let output = copar(&["check", "missing.log"], "");
+    assert_eq!(output.status.code(), Some(74));
+}
+
+#[test]
+fn test_check_outputs() {
+    let dir = TestDir::new("cli_check_outputs");
+    let log_path = dir.path().join("init.log");
+    let rust_path = dir.path().join("init.rs");
+    fs::write(&log_path, LOG).unwrap();
+    let log_path = log_path.to_str().unwrap();
+    let rust_path_str = rust_path.to_str().unwrap();
+
+    let output = copar(&["generate-rust", log_path, rust_path_str], "");
+    assert_eq!(output.status.code(), Some(0));
+    let output = copar(&["generate-rust", log_path, rust_path_str, "--check"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    // An outdated file is reported with its diff and left untouched
+    fs::write(&rust_path, "outdated\n").unwrap();
+    let output = copar(&["generate-rust", log_path, rust_path_str, "--check"], "");
+    assert_eq!(output.status.code(), Some(1));
+    let diff = String::from_utf8(output.stdout).unwrap();
+    assert!(diff.starts_with(&format!("--- {rust_path_str}\n+++ {rust_path_str}\n")));
+    assert!(diff.contains("\n-outdated\n"));
+    assert_eq!(fs::read_to_string(&rust_path).unwrap(), "outdated\n");
+
+    let output = copar(&["generate-rust", log_path, "-", "--check"], "");
+    assert_eq!(output.status.code(), Some(64));
+}
@@ -1,10 +1,43 @@
 mod test_files;
-use copar::{CGeneration, CSharpGeneration, Parser, RustGeneration};
+use copar::{
+    CEmitterGeneration, CGeneration, CSharpGeneration, CheckSeverity, EnumDiscriminantError,
+    FileParsingError, LineParsingError, Model, Parser, RustGeneration, StructDefinitionError,
+    TimestampFormat,
+};
 
 use test_files::{TEST_FILE_C_CONTENT, TEST_FILE_H_CONTENT, TEST_FILE_LOG};
 
 use crate::test_files::{TEST_FILE_CS_CONTENT, TEST_FILE_RUST_CONTENT};
 
+fn generate_rust(model: &Model) -> String {
+    let mut output_rs = Vec::new();
+    model.compute_to_rust(&mut output_rs);
+    String::from_utf8(output_rs).unwrap()
+}
+
+/// Generated C source and header
+fn generate_c(model: &Model) -> (String, String) {
+    let mut output_c = Vec::new();
+    let mut output_h = Vec::new();
+    model.compute_to_c(&mut output_c, &mut output_h);
+    (
+        String::from_utf8(output_c).unwrap(),
+        String::from_utf8(output_h).unwrap(),
+    )
+}
+
+fn generate_csharp(model: &Model) -> String {
+    let mut output_cs = Vec::new();
+    model.compute_to_cs(&mut output_cs);
+    String::from_utf8(output_cs).unwrap()
+}
+
+fn generate_c_emitter(model: &Model) -> String {
+    let mut output_h = Vec::new();
+    model.compute_to_c_emitter(&mut output_h);
+    String::from_utf8(output_h).unwrap()
+}
+
 #[test]
 fn test_c_gen() {
     let model = Parser::parse(TEST_FILE_LOG.as_bytes()).unwrap();
@@ -50,3 +83,680 @@ fn test_csharp_gen() {
         TEST_FILE_CS_CONTENT
     );
 }
+
+#[test]
+fn test_delay_gen() {
+    let log = "\
+[    1.000100] #< timed_sequence <#
+[    1.000200] #= Cmd52 add: x32(0x10) =#
+[    1.000700] #= Cmd52 add: x32(0x11) =#
+[    1.000800] #~ delay ms: u16(2) ~#
+[    1.002000] #= Cmd52 add: x32(0x10) =#
+[    1.002100] #~ delay us: u32(100) ~#
+[    1.003000] #= Cmd52 add: x32(0x11) =#
+[    1.004000] #> timed_sequence >#
+";
+    let model = Parser::new(log.as_bytes())
+        .with_timestamps(TimestampFormat::KernelLog)
+        .parse_file()
+        .unwrap();
+    assert!(generate_rust(&model)
+        .ends_with("pub static TIMED_SEQUENCE_DELAYS_US: &[u32] = &[0, 500, 2000, 1000];"));
+
+    let trailing_delay_log = "\
+#< init <#
+#= Cmd52 add: x32(0x10) =#
+#~ delay ms: u16(2) ~#
+#> init >#
+";
+    assert!(matches!(
+        Parser::parse(trailing_delay_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 4,
+            line_error: LineParsingError::TrailingDelay,
+        })
+    ));
+
+    let multiline_delay_log = "\
+#< init <#
+#~ delay ms: u16(2)
+us: u32(5) ~#
+#= Cmd52 add: x32(0x10) =#
+#> init >#
+";
+    assert!(matches!(
+        Parser::parse(multiline_delay_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 2,
+            line_error: LineParsingError::MissingEndDelimiter('~'),
+        })
+    ));
+}
+
+#[test]
+fn test_c_emitter_gen() {
+    let log = "\
+#< init <#
+#= Cmd53 add: x32(0x1043) data: x8([0x80,0x05]) dv_sel: id(EfuseAccess::DDV) =#
+#? Cmd52 add: x32(0x1043) expect: x8(0x80) ?#
+#> init >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    let output_h = generate_c_emitter(&model);
+
+    let expected_functions = r##"
+static inline void copar_log_cmd53(uint32_t add, const uint8_t *data, size_t data_len, const char* dv_sel)
+{
+   COPAR_LOG_PRINTF("#= Cmd53 add: x32(0x%lx) data: x8([", (unsigned long)add);
+   for (size_t copar_index = 0; copar_index < data_len; copar_index++) {
+      if (copar_index > 0) COPAR_LOG_PRINTF(",");
+      COPAR_LOG_PRINTF("0x%x", (unsigned int)data[copar_index]);
+   }
+   COPAR_LOG_PRINTF("]) dv_sel: id(EfuseAccess::%s) =#\n", dv_sel);
+}
+
+static inline void copar_log_cmd52_expect(uint32_t add, uint8_t expect, uint8_t mask)
+{
+   COPAR_LOG_PRINTF("#? Cmd52 add: x32(0x%lx) expect: x8(0x%x) mask: x8(0x%x) ?#\n", (unsigned long)add, (unsigned int)expect, (unsigned int)mask);
+}
+
+#endif"##;
+    assert!(output_h.starts_with(concat!(
+        "// Generated by copar ",
+        env!("CARGO_PKG_VERSION"),
+        ", do not edit.\n"
+    )));
+    assert!(output_h.contains("\n\n#ifndef _INIT_EMIT_H\n"));
+    assert!(output_h.ends_with(expected_functions), "{output_h}");
+
+    // Records named like the generated operations are logged when there is no call or repeat
+    let logged_log = "\
+#< init <#
+#= Repeat count: u32(2) body_len: u32(1) =#
+#= Call seq: id(Seq::init) =#
+#> init >#
+";
+    let model = Parser::parse(logged_log.as_bytes()).unwrap();
+    let output_h = generate_c_emitter(&model);
+    assert!(output_h.contains("static inline void copar_log_repeat("));
+    assert!(output_h.contains("static inline void copar_log_call("));
+
+    // Only the expected-value records are printed as such
+    let logged_expect_log = "\
+#< init <#
+#= ReadExpect expect: x8(0x80) =#
+#> init >#
+";
+    let model = Parser::parse(logged_expect_log.as_bytes()).unwrap();
+    assert!(generate_c_emitter(&model).contains(
+        "   COPAR_LOG_PRINTF(\"#= ReadExpect expect: x8(0x%x) =#\\n\", (unsigned int)expect);"
+    ));
+}
+
+#[test]
+fn test_expect_record_name() {
+    let expectation = "#? Cmd52 expect: x8(0x80) ?#";
+    let logged_record = "#= Cmd52Expect expect: x8(0x80) mask: x8(0xff) =#";
+    for records in [[expectation, logged_record], [logged_record, expectation]] {
+        let log = format!("#< init <#\n{}\n{}\n#> init >#\n", records[0], records[1]);
+        assert!(matches!(
+            Parser::parse(log.as_bytes()),
+            Err(FileParsingError::LineError {
+                line_nb: 3,
+                line_error: LineParsingError::ReservedExpectRecord(name),
+            }) if name == "Cmd52Expect"
+        ));
+    }
+}
+
+#[test]
+fn test_sequence_call_gen() {
+    let log = "\
+#< power_on <#
+#= Cmd52 add: x32(0x10) =#
+#> power_on >#
+#< resume <#
+#= @call seq: id(Seq::power_on) =#
+#> resume >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    assert!(generate_rust(&model).ends_with(
+        "\
+pub static POWER_ON: &[OperationVariant] = &[
+    OperationVariant::Cmd52(&OPERATION_0),
+];
+
+pub static RESUME: &[OperationVariant] = &[
+    OperationVariant::Call(&OPERATION_1),
+];"
+    ));
+
+    let mixed_case_log = "\
+#< PowerOn <#
+#= Cmd52 add: x32(0x10) =#
+#> PowerOn >#
+";
+    let model = Parser::parse(mixed_case_log.as_bytes()).unwrap();
+    let (output_c, _) = generate_c(&model);
+    assert!(output_c.contains("const Operation PowerOn[] = {"));
+    assert!(output_c.ends_with("const uint32_t PowerOn_len = sizeof(PowerOn)/sizeof(Operation);"));
+
+    let recursive_log = "\
+#< power_on <#
+#= @call seq: id(Seq::resume) =#
+#> power_on >#
+#< resume <#
+#= @call seq: id(Seq::power_on) =#
+#> resume >#
+";
+    assert!(matches!(
+        Parser::parse(recursive_log.as_bytes()),
+        Err(FileParsingError::RecursiveSequenceCall { .. })
+    ));
+
+    let logged_call_log = "\
+#< power_on <#
+#= Call seq: id(Seq::power_on) =#
+#> power_on >#
+#< resume <#
+#= @call seq: id(Seq::power_on) =#
+#> resume >#
+";
+    assert!(matches!(
+        Parser::parse(logged_call_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 5,
+            line_error: LineParsingError::ReservedCallRecord,
+        })
+    ));
+
+    let malformed_begin_log = "\
+#< power_on <#
+#= Cmd52 add: x32(0x10) =#
+#> power_on >#
+#< resume
+#= @call seq: id(Seq::power_on) =#
+#> resume >#
+";
+    assert!(matches!(
+        Parser::parse(malformed_begin_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 4,
+            line_error: LineParsingError::MissingEndDelimiter('<'),
+        })
+    ));
+}
+
+#[test]
+fn test_phase_gen() {
+    let log = "\
+#< init <#
+#= Cmd52 add: x32(0x10) =#
+#< phase clock_setup <#
+#= Cmd52 add: x32(0x11) =#
+#< phase pll <#
+#= Cmd52 add: x32(0x12) =#
+#> phase pll >#
+#> phase clock_setup >#
+#= Cmd52 add: x32(0x13) =#
+#> init >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    assert!(generate_rust(&model).ends_with(
+        "\
+pub static INIT: &[OperationVariant] = &[
+    OperationVariant::Cmd52(&OPERATION_0),
+    // phase clock_setup
+    OperationVariant::Cmd52(&OPERATION_1),
+    // phase clock_setup.pll
+    OperationVariant::Cmd52(&OPERATION_2),
+    // end of phase clock_setup.pll
+    // end of phase clock_setup
+    OperationVariant::Cmd52(&OPERATION_3),
+];
+
+pub const INIT_PHASE_CLOCK_SETUP: core::ops::Range<usize> = 1..3;
+pub const INIT_PHASE_CLOCK_SETUP_PLL: core::ops::Range<usize> = 2..3;"
+    ));
+
+    let unterminated_log = "\
+#< init <#
+#< phase clock_setup <#
+#= Cmd52 add: x32(0x10) =#
+#> init >#
+";
+    assert!(matches!(
+        Parser::parse(unterminated_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 4,
+            line_error: LineParsingError::UnterminatedPhase(_),
+        })
+    ));
+}
+
+#[test]
+fn test_check() {
+    let log = "\
+#< init <#
+#= Cmd52 add: x32(0x10) mode: id(Mode::fast) =#
+#= Cmd52 add: x32(0x10) mode: id(Mode::fast) =#
+#= Cmd52 add: u16(16) =#
+#= Cmd53 speed: id(Speed::fast) =#
+#[ Blk [#
+#- Blk a: u8(1) -#
+#> init >#
+";
+    let issues: Vec<(Option<usize>, CheckSeverity)> = Parser::new(log.as_bytes())
+        .check()
+        .iter()
+        .map(|issue| (issue.line_nb, issue.severity))
+        .collect();
+
+    assert_eq!(
+        issues,
+        vec![
+            (Some(3), CheckSeverity::Warning),
+            (Some(4), CheckSeverity::Error),
+            (Some(5), CheckSeverity::Warning),
+            (Some(8), CheckSeverity::Error),
+        ]
+    );
+    assert!(Parser::new(TEST_FILE_LOG.as_bytes())
+        .check()
+        .iter()
+        .all(|issue| issue.severity == CheckSeverity::Warning));
+}
+
+#[test]
+fn test_sequence_selection() {
+    let log = "\
+#< power_on <#
+#= Cmd52 add: x32(0x10) data: x8([0x1,0x2]) =#
+#> power_on >#
+#< resume <#
+#= Cmd52 add: x32(0x11) data: x8([0x3]) =#
+#> resume >#
+";
+    let mut model = Parser::parse(log.as_bytes()).unwrap();
+    assert!(model.select_sequences(&["suspend"]).is_err());
+    model.select_sequences(&["resume"]).unwrap();
+    let output = generate_rust(&model);
+
+    assert!(!output.contains("ARRAY_0") && !output.contains("OPERATION_0"));
+    assert!(output.ends_with(
+        "\
+pub static RESUME: &[OperationVariant] = &[
+    OperationVariant::Cmd52(&OPERATION_1),
+];"
+    ));
+}
+
+#[test]
+fn test_sequence_selection_calls() {
+    let log = "\
+#< power_on <#
+#= Cmd52 add: x32(0x10) =#
+#> power_on >#
+#< resume <#
+#= @call seq: id(Seq::power_on) =#
+#= Cmd53 mode: id(Mode::Fast) =#
+#> resume >#
+#< suspend <#
+#= Cmd54 bits: flags(CtrlBits::EN) =#
+#> suspend >#
+";
+    let mut model = Parser::parse(log.as_bytes()).unwrap();
+    model.select_sequences(&["resume"]).unwrap();
+    assert_eq!(
+        model.sequence_names().collect::<Vec<_>>(),
+        ["power_on", "resume"]
+    );
+    let output = generate_rust(&model);
+
+    assert!(output.contains("pub static POWER_ON: &[OperationVariant]"));
+    assert!(output.contains("pub enum Mode"));
+    assert!(!output.contains("Cmd54") && !output.contains("CtrlBits"));
+}
+
+#[test]
+fn test_enum_discriminant_gen() {
+    let log = "\
+#< init <#
+#= Efuse access: id(EfuseAccess::DAV) =#
+#= Efuse access: id(EfuseAccess::DDV=0x3) =#
+#= Efuse access: id(EfuseAccess::DAV=0x1) =#
+#= Efuse access: id(EfuseAccess::OFF) =#
+#> init >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    assert!(generate_rust(&model).contains(
+        "\
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EfuseAccess {
+    Dav = 0x1,
+    Ddv = 0x3,
+    Off,
+}"
+    ));
+    let (_, output_h) = generate_c(&model);
+    assert!(output_h.contains(
+        "\
+enum EfuseAccess{
+   EfuseAccessDAV = 0x1,
+   EfuseAccessDDV = 0x3,
+   EfuseAccessOFF,
+};"
+    ));
+    assert!(generate_csharp(&model).contains(
+        "    public enum EfuseAccess : uint {
+        DAV = 0x1,
+        DDV = 0x3,
+        OFF,
+    }"
+    ));
+
+    for conflicting_record in [
+        "#= Efuse access: id(EfuseAccess::DDV=0x4) =#",
+        "#= Efuse access: id(EfuseAccess::OFF=0x1) =#",
+    ] {
+        let conflicting_log =
+            log.replace("#> init >#", &format!("{conflicting_record}\n#> init >#"));
+        assert!(matches!(
+            Parser::parse(conflicting_log.as_bytes()),
+            Err(FileParsingError::LineError {
+                line_nb: 6,
+                line_error: LineParsingError::EnumDiscriminant(_),
+            })
+        ));
+    }
+
+    // The discriminants must fit in a C enum constant
+    let int_max_log = log
+        .replace("DDV=0x3", "DDV=0x7fffffff")
+        .replace("#= Efuse access: id(EfuseAccess::OFF) =#\n", "");
+    assert!(generate_c(&Parser::parse(int_max_log.as_bytes()).unwrap())
+        .1
+        .contains("   EfuseAccessDDV = 0x7fffffff,\n"));
+    for overflowing_log in [
+        log.replace("DDV=0x3", "DDV=0x80000000"),
+        log.replace("DDV=0x3", "DDV=0x7fffffff"),
+    ] {
+        assert!(matches!(
+            Parser::parse(overflowing_log.as_bytes()),
+            Err(FileParsingError::LineError {
+                line_error: LineParsingError::EnumDiscriminant(error),
+                ..
+            }) if matches!(*error, EnumDiscriminantError::Overflow { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_flags_gen() {
+    let log = "\
+#< init <#
+#= Ctrl reg: x8(0x4) bits: flags(CtrlBits::EN|CtrlBits::IRQ) =#
+#= Ctrl reg: x8(0x4) bits: flags(CtrlBits::IRQ|CtrlBits::EN) =#
+#= Ctrl reg: x8(0x5) bits: flags(CtrlBits) =#
+#> init >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    let output = generate_rust(&model);
+    assert!(output.contains(
+        "\
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CtrlBits(pub u32);
+
+impl CtrlBits {
+    pub const EN: CtrlBits = CtrlBits(0x1);
+    pub const IRQ: CtrlBits = CtrlBits(0x2);
+}"
+    ));
+    // The same flags in another order are the same instance
+    assert!(output.contains(
+        "\
+pub static OPERATION_0: Ctrl = Ctrl { reg: 0x4, bits: CtrlBits(CtrlBits::EN.0 | CtrlBits::IRQ.0) };
+pub static OPERATION_1: Ctrl = Ctrl { reg: 0x5, bits: CtrlBits(0) };"
+    ));
+
+    let (output_c, output_h) = generate_c(&model);
+    assert!(output_h.contains(
+        "\
+#define CtrlBitsEN 0x1u
+#define CtrlBitsIRQ 0x2u
+"
+    ));
+    assert!(output_c
+        .contains("const Ctrl operation_0 = {.reg = 0x4, .bits = CtrlBitsEN | CtrlBitsIRQ};"));
+
+    assert!(generate_csharp(&model).contains(
+        "    [System.Flags]
+    public enum CtrlBits : uint {
+        EN = 0x1,
+        IRQ = 0x2,
+    }"
+    ));
+
+    let all_flags: String = (0..30)
+        .map(|index| format!("CtrlBits::B{index}"))
+        .collect::<Vec<String>>()
+        .join("|");
+    let all_flags_log = log.replace("CtrlBits::IRQ|CtrlBits::EN", &all_flags);
+    let (_, output_h) = generate_c(&Parser::parse(all_flags_log.as_bytes()).unwrap());
+    assert!(output_h.contains("#define CtrlBitsB29 0x80000000u\n"));
+
+    let too_many_flags: String = (0..33)
+        .map(|index| format!("CtrlBits::B{index}"))
+        .collect::<Vec<String>>()
+        .join("|");
+    let too_many_flags_log = log.replace("CtrlBits::IRQ|CtrlBits::EN", &too_many_flags);
+    assert!(matches!(
+        Parser::parse(too_many_flags_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 3,
+            line_error: LineParsingError::TooManyFlags(_),
+        })
+    ));
+}
+
+#[test]
+fn test_int128_gen() {
+    let log = "\
+#< crypto <#
+#= Key key: x128(0x1ee3fb4fad554ce05da5708c1) counter: u128(7) nonces: i128([-1,-170141183460469231731687303715884105728]) =#
+#> crypto >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    let output = generate_rust(&model);
+    assert!(output.contains(
+        "pub static ARRAY_0: & [i128] = &[-1, -170141183460469231731687303715884105728];"
+    ));
+    assert!(output.contains(
+        "pub static OPERATION_0: Key = Key { key: 0x1ee3fb4fad554ce05da5708c1, counter: 7, nonces: ARRAY_0 };"
+    ));
+
+    let (output_c, output_h) = generate_c(&model);
+    assert!(output_h.contains("#ifdef COPAR_NATIVE_INT128"));
+    assert!(output_h.contains("   const copar_i128* const nonces;"));
+    assert!(output_c.contains(
+        "const copar_i128 array_0[] = {COPAR_I128(0xffffffffffffffff, 0xffffffffffffffff), COPAR_I128(0x8000000000000000, 0x0)};"
+    ));
+    assert!(output_c.contains(
+        "const Key operation_0 = {.key = COPAR_U128(0x1ee3fb4fa, 0xd554ce05da5708c1), .counter = COPAR_U128(0x0, 0x7), .nonces = array_0};"
+    ));
+
+    let output_cs = generate_csharp(&model);
+    assert!(output_cs.contains(
+        "public static readonly Int128[] Array0 =  { -1, new Int128(0x8000000000000000, 0x0) };"
+    ));
+    assert!(output_cs.contains("key = new UInt128(0x1ee3fb4fa, 0xd554ce05da5708c1), counter = 7"));
+
+    assert!(generate_c_emitter(&model).contains(
+        "static inline void copar_log_key(uint64_t key_high, uint64_t key_low, uint64_t counter_high, uint64_t counter_low, const struct copar_i128_words *nonces, size_t nonces_len)"
+    ));
+}
+
+#[test]
+fn test_struct_gen() {
+    let log = "\
+#< dma <#
+#= Dma desc: {src: x32(0x1000) len: u16(64)} ch: u8(1) =#
+#= Dma desc: {src: x32(0x1000) len: u16(64)} ch: u8(2) =#
+#= Chain descs: [Desc{src: x32(0x10) len: u16(4)},Desc{src: x32(0x1000) len: u16(64)}] =#
+#> dma >#
+";
+    let model = Parser::parse(log.as_bytes()).unwrap();
+    let output = generate_rust(&model);
+    assert!(output.contains("pub struct Desc {\n    pub src: u32,\n    pub len: u16,\n}"));
+    assert!(output.contains("    pub descs: &'static [&'static Desc],"));
+    assert!(output.contains("pub static STRUCT_0: Desc = Desc { src: 0x1000, len: 64 };"));
+    assert!(output.contains("pub static STRUCT_ARRAY_2: &[&Desc] = &[&STRUCT_1, &STRUCT_0];"));
+    assert!(output.contains("pub static OPERATION_1: Dma = Dma { desc: &STRUCT_0, ch: 2 };"));
+
+    let (output_c, output_h) = generate_c(&model);
+    assert!(output_h.contains("   const Desc* const* const descs;"));
+    assert!(output_c.contains("const Desc struct_0 = {.src = 0x1000, .len = 64};"));
+    assert!(output_c.contains("const Desc* const struct_array_2[] = {&struct_1, &struct_0};"));
+    assert!(output_c.contains("const Chain operation_2 = {.descs = struct_array_2};"));
+
+    let output_cs = generate_csharp(&model);
+    assert!(output_cs
+        .contains("public static readonly Desc Struct0 = new Desc { src = 0x1000, len = 64 };"));
+    assert!(output_cs.contains("new Dma { desc = Struct0, ch = 1 }"));
+
+    let output_emitter = generate_c_emitter(&model);
+    assert!(output_emitter.contains(
+        "static inline void copar_print_struct_desc(const struct copar_desc *copar_value)"
+    ));
+    assert!(output_emitter.contains(
+        "static inline void copar_log_chain(const struct copar_desc *descs, size_t descs_len)"
+    ));
+
+    let redefined_log = "\
+#< dma <#
+#= Dma desc: {src: x32(0x1000) len: u16(4)} =#
+#= Dma desc: {dst: u8(3)} =#
+#> dma >#
+";
+    assert!(matches!(
+        Parser::parse(redefined_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 3,
+            line_error: LineParsingError::StructDefinition(StructDefinitionError::Redefined(_)),
+        })
+    ));
+
+    let record_name_log = "\
+#< dma <#
+#= Dma desc: {src: x32(0x1000) len: u16(4)} =#
+#= Other d: Dma{x: u8(1)} =#
+#> dma >#
+";
+    assert!(matches!(
+        Parser::parse(record_name_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 3,
+            line_error: LineParsingError::StructDefinition(StructDefinitionError::RecordName(_)),
+        })
+    ));
+
+    let struct_name_log = "\
+#< dma <#
+#= Other d: Dma{x: u8(1)} =#
+#= Dma ch: u8(1) =#
+#> dma >#
+";
+    assert!(matches!(
+        Parser::parse(struct_name_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 3,
+            line_error: LineParsingError::StructDefinition(StructDefinitionError::RecordName(_)),
+        })
+    ));
+}
+
+#[test]
+fn test_sequence_metadata_gen() {
+    let log = "\
+#< init device=rtl8822cs driver_version=\"5.6.1 beta\" <#
+#= Cmd52 add: x32(0x10) =#
+#> init >#
+";
+    let model = Parser::new(log.as_bytes())
+        .with_file_name("init.log")
+        .parse_file()
+        .unwrap();
+    assert_eq!(
+        model.sequence_metadata("init"),
+        Some(
+            &[
+                (String::from("device"), String::from("rtl8822cs")),
+                (String::from("driver_version"), String::from("5.6.1 beta")),
+            ][..]
+        )
+    );
+    let (output_c, output_h) = generate_c(&model);
+    let expected_comment = format!(
+        "\
+// Generated by copar {} from init.log, do not edit.
+// Log content hash (FNV-1a 64): 0x",
+        env!("CARGO_PKG_VERSION")
+    );
+    for output in [output_c, output_h] {
+        assert!(output.starts_with(&expected_comment), "{output}");
+        assert!(
+            output.contains("// Sequence init: device=rtl8822cs, driver_version=5.6.1 beta\n\n")
+        );
+    }
+
+    let duplicate_key_log = log.replace("driver_version", "device");
+    assert!(matches!(
+        Parser::parse(duplicate_key_log.as_bytes()),
+        Err(FileParsingError::LineError {
+            line_nb: 1,
+            line_error: LineParsingError::BadSequenceMetadata(_),
+        })
+    ));
+}
+
+#[test]
+fn test_source_annotation_gen() {
+    let log = "\
+driver probe
+#< init <#
+#= Cmd52 add: x32(0x10) =#
+#= Cmd52 add: x32(0x10) =#
+#= Cmd52 add: x32(0x10) =#
+#= Cmd53 add: x32(0x10)
+data: x8(0x1) =#
+#? Cmd52 add: x32(0x10) expect: x8(0x1) ?#
+#> init >#
+";
+    let mut model = Parser::new(log.as_bytes())
+        .with_file_name("init.log")
+        .parse_file()
+        .unwrap();
+    assert_eq!(
+        model.operation_source("init", 3),
+        Some((6, "Cmd53 add: x32(0x10) data: x8(0x1)"))
+    );
+    model.set_source_annotations(true);
+    model.compress().unwrap();
+    assert_eq!(model.operation_source("init", 0), None);
+
+    assert!(generate_rust(&model).ends_with(
+        "\
+pub static INIT: &[OperationVariant] = &[
+    OperationVariant::Repeat(&OPERATION_3),
+    OperationVariant::Cmd52(&OPERATION_0), // init.log:3 Cmd52
+    OperationVariant::Cmd53(&OPERATION_1), // init.log:6 Cmd53
+    OperationVariant::Cmd52Expect(&OPERATION_2), // init.log:8 Cmd52Expect
+];"
+    ));
+
+    let (output_c, _) = generate_c(&model);
+    assert!(output_c.contains(
+        "   {.id = OPERATION_ID_CMD52_EXPECT, .variant={.cmd52_expect=&operation_2}} // init.log:8 Cmd52Expect\n};"
+    ));
+}
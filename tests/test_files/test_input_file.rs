@@ -15,7 +15,7 @@ data: x8([0x80,0x05,
 #[ ReadEfuse [#
 #- ReadEfuse offset: x16(0x5ea) efuse_start: x8(0x0) size: u8(1) read_efuse_cnt: u32(10000) efuse_ctrl: x8(0x30) dv_sel: id(EfuseAccess::DDV) -#
 #= Cmd52 write: bool(true) ln: u8(1) add: x32(0x01043) data: x8(0x80) =#
-#- ReadEfuse map_ptr: x64(00000000da5708c1) -#
+#- ReadEfuse map_ptr: x64(0x00000000da5708c1) -#
 I am not a record
 e neither
 #] ReadEfuse ]#
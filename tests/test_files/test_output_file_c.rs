@@ -1,4 +1,9 @@
-pub static TEST_FILE_C_CONTENT: &str = "\
+pub static TEST_FILE_C_CONTENT: &str = concat!(
+    "// Generated by copar ",
+    env!("CARGO_PKG_VERSION"),
+    ", do not edit.
+// Log content hash (FNV-1a 64): 0x37883d59a96553c2
+
 #include \"playdisc.h\"
 
 const uint8_t array_0[] = {0x80, 0x5};
@@ -20,4 +25,5 @@ const Operation test_sequence[] = {
    {.id = OPERATION_ID_ANOTHER_CMD, .variant={.another_cmd=&operation_4}}
 };
 
-const uint32_t test_sequence_len = sizeof(test_sequence_operations)/sizeof(Operation);";
+const uint32_t test_sequence_len = sizeof(test_sequence)/sizeof(Operation);"
+);
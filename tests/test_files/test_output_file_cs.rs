@@ -1,4 +1,9 @@
-pub static TEST_FILE_CS_CONTENT: &str = "\
+pub static TEST_FILE_CS_CONTENT: &str = concat!(
+    "// Generated by copar ",
+    env!("CARGO_PKG_VERSION"),
+    ", do not edit.
+// Log content hash (FNV-1a 64): 0x37883d59a96553c2
+
 namespace GeneratedTestSequence
 {
     public enum OperationId {
@@ -78,4 +83,5 @@ namespace GeneratedTestSequence
             new Operation(OperationId.AnotherCmd, Operation4),
         };
     }
-}";
+}"
+);
@@ -1,4 +1,9 @@
-pub static TEST_FILE_H_CONTENT: &str = "\
+pub static TEST_FILE_H_CONTENT: &str = concat!(
+    "// Generated by copar ",
+    env!("CARGO_PKG_VERSION"),
+    ", do not edit.
+// Log content hash (FNV-1a 64): 0x37883d59a96553c2
+
 #ifndef _TEST_SEQUENCE_H
 #define _TEST_SEQUENCE_H
 #include <stdint.h>
@@ -62,4 +67,5 @@ typedef struct{
    const union OperationVariant variant;
 }Operation;
 
-#endif";
+#endif"
+);
@@ -1,4 +1,9 @@
-pub static TEST_FILE_RUST_CONTENT: &str = "\
+pub static TEST_FILE_RUST_CONTENT: &str = concat!(
+    "// Generated by copar ",
+    env!("CARGO_PKG_VERSION"),
+    ", do not edit.
+// Log content hash (FNV-1a 64): 0x37883d59a96553c2
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OperationId {
     Cmd52,
@@ -76,4 +81,5 @@ pub static TEST_SEQUENCE: &[OperationVariant] = &[
     OperationVariant::Cmd53(&OPERATION_2),
     OperationVariant::ReadEfuse(&OPERATION_3),
     OperationVariant::AnotherCmd(&OPERATION_4),
-];";
+];"
+);